@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::accounts::CheckAccess;
+use crate::AccessCheck;
+
+pub fn check_access<'info>(
+    synid_program: AccountInfo<'info>,
+    synid: AccountInfo<'info>,
+    access_grant: AccountInfo<'info>,
+    requester: Pubkey,
+    fields: Vec<String>,
+) -> Result<AccessCheck> {
+    let ctx = CpiContext::new(synid_program, CheckAccess { synid, access_grant });
+    Ok(crate::cpi::check_access(ctx, requester, fields)?.get())
+}
+
+pub fn has_access<'info>(
+    synid_program: AccountInfo<'info>,
+    synid: AccountInfo<'info>,
+    access_grant: AccountInfo<'info>,
+    requester: Pubkey,
+    fields: Vec<String>,
+) -> Result<bool> {
+    Ok(check_access(synid_program, synid, access_grant, requester, fields)?.is_granted())
+}
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::Pubkey;
+use chacha20poly1305::aead::{Aead, NewAead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use rand_core::OsRng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::WrappedFieldKey;
+
+const KDF_INFO: &[u8] = b"synid-key-envelope-v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeError {
+    SealFailed,
+    OpenFailed,
+}
+
+impl std::fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::SealFailed => write!(f, "failed to seal field key"),
+            EnvelopeError::OpenFailed => write!(f, "failed to open field key"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+pub fn public_key(secret: &[u8; 32]) -> [u8; 32] {
+    PublicKey::from(&StaticSecret::from(*secret)).to_bytes()
+}
+
+// The ephemeral secret is generated here and dropped after use, so a (key, nonce) pair
+// can never repeat across envelopes.
+pub fn seal_field_keys(
+    recipient_pubkey: &[u8; 32],
+    access_grant: &Pubkey,
+    field_keys: &[(String, [u8; 32])],
+) -> Result<([u8; 32], Vec<WrappedFieldKey>), EnvelopeError> {
+    let ephemeral = EphemeralSecret::new(OsRng);
+    let ephemeral_pubkey = PublicKey::from(&ephemeral).to_bytes();
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(*recipient_pubkey));
+
+    let mut keys = Vec::with_capacity(field_keys.len());
+    for (field, content_key) in field_keys {
+        let cipher = field_cipher(
+            shared.as_bytes(),
+            &ephemeral_pubkey,
+            recipient_pubkey,
+            access_grant,
+            field,
+        );
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&[0u8; 12]),
+                Payload { msg: content_key, aad: field.as_bytes() },
+            )
+            .map_err(|_| EnvelopeError::SealFailed)?;
+        let mut ciphertext = [0u8; WrappedFieldKey::CIPHERTEXT_LEN];
+        ciphertext.copy_from_slice(&sealed);
+        keys.push(WrappedFieldKey { field: field.clone(), ciphertext });
+    }
+
+    Ok((ephemeral_pubkey, keys))
+}
+
+pub fn open_field_key(
+    recipient_secret: &[u8; 32],
+    ephemeral_pubkey: &[u8; 32],
+    access_grant: &Pubkey,
+    wrapped: &WrappedFieldKey,
+) -> Result<[u8; 32], EnvelopeError> {
+    let recipient = StaticSecret::from(*recipient_secret);
+    let recipient_pubkey = PublicKey::from(&recipient).to_bytes();
+    let shared = recipient.diffie_hellman(&PublicKey::from(*ephemeral_pubkey));
+
+    let cipher = field_cipher(
+        shared.as_bytes(),
+        ephemeral_pubkey,
+        &recipient_pubkey,
+        access_grant,
+        &wrapped.field,
+    );
+    let opened = cipher
+        .decrypt(
+            Nonce::from_slice(&[0u8; 12]),
+            Payload { msg: &wrapped.ciphertext, aad: wrapped.field.as_bytes() },
+        )
+        .map_err(|_| EnvelopeError::OpenFailed)?;
+
+    let mut content_key = [0u8; 32];
+    content_key.copy_from_slice(&opened);
+    Ok(content_key)
+}
+
+// Each field gets its own key, so the fixed nonce is never reused under one key.
+fn field_cipher(
+    shared: &[u8; 32],
+    ephemeral_pubkey: &[u8; 32],
+    recipient_pubkey: &[u8; 32],
+    access_grant: &Pubkey,
+    field: &str,
+) -> ChaCha20Poly1305 {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_pubkey);
+    salt[32..].copy_from_slice(recipient_pubkey);
+
+    let mut info = KDF_INFO.to_vec();
+    info.extend_from_slice(access_grant.as_ref());
+    info.extend_from_slice(field.as_bytes());
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(&info, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPIENT_SECRET: [u8; 32] = [7u8; 32];
+
+    fn field_keys() -> Vec<(String, [u8; 32])> {
+        vec![("email".to_string(), [1u8; 32]), ("country".to_string(), [2u8; 32])]
+    }
+
+    fn seal(access_grant: &Pubkey) -> ([u8; 32], Vec<WrappedFieldKey>) {
+        seal_field_keys(&public_key(&RECIPIENT_SECRET), access_grant, &field_keys()).unwrap()
+    }
+
+    #[test]
+    fn seal_then_open_round_trips() {
+        let access_grant = Pubkey::new_unique();
+        let (ephemeral_pubkey, wrapped) = seal(&access_grant);
+
+        assert_eq!(wrapped.len(), 2);
+        for (wrapped, (field, content_key)) in wrapped.iter().zip(field_keys()) {
+            assert_eq!(wrapped.field, field);
+            let opened =
+                open_field_key(&RECIPIENT_SECRET, &ephemeral_pubkey, &access_grant, wrapped).unwrap();
+            assert_eq!(opened, content_key);
+        }
+    }
+
+    #[test]
+    fn each_seal_uses_a_fresh_ephemeral_key() {
+        let access_grant = Pubkey::new_unique();
+        let (first_pubkey, first) = seal(&access_grant);
+        let (second_pubkey, second) = seal(&access_grant);
+
+        assert_ne!(first_pubkey, second_pubkey);
+        assert_ne!(first[0].ciphertext, second[0].ciphertext);
+    }
+
+    #[test]
+    fn open_with_wrong_recipient_fails() {
+        let access_grant = Pubkey::new_unique();
+        let (ephemeral_pubkey, wrapped) = seal(&access_grant);
+
+        assert_eq!(
+            open_field_key(&[9u8; 32], &ephemeral_pubkey, &access_grant, &wrapped[0]),
+            Err(EnvelopeError::OpenFailed)
+        );
+    }
+
+    #[test]
+    fn open_with_tampered_ciphertext_fails() {
+        let access_grant = Pubkey::new_unique();
+        let (ephemeral_pubkey, mut wrapped) = seal(&access_grant);
+        wrapped[0].ciphertext[0] ^= 1;
+
+        assert_eq!(
+            open_field_key(&RECIPIENT_SECRET, &ephemeral_pubkey, &access_grant, &wrapped[0]),
+            Err(EnvelopeError::OpenFailed)
+        );
+    }
+
+    #[test]
+    fn open_under_another_grant_or_field_fails() {
+        let access_grant = Pubkey::new_unique();
+        let (ephemeral_pubkey, mut wrapped) = seal(&access_grant);
+
+        assert_eq!(
+            open_field_key(&RECIPIENT_SECRET, &ephemeral_pubkey, &Pubkey::new_unique(), &wrapped[0]),
+            Err(EnvelopeError::OpenFailed)
+        );
+
+        wrapped[0].field = "country".to_string();
+        assert_eq!(
+            open_field_key(&RECIPIENT_SECRET, &ephemeral_pubkey, &access_grant, &wrapped[0]),
+            Err(EnvelopeError::OpenFailed)
+        );
+    }
+}
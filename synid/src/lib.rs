@@ -1,10 +1,26 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Burn};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    metadata_pointer, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::{self, spl_token_2022, Token2022};
+use anchor_spl::token_interface::{self, TokenInterface};
+use anchor_spl::associated_token::{self, AssociatedToken};
 use anchor_spl::metadata::{
-    create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata,
-    mpl_token_metadata::types::DataV2,
+    create_master_edition_v3, create_metadata_accounts_v3, update_metadata_accounts_v2,
+    verify_sized_collection_item, CreateMasterEditionV3, CreateMetadataAccountsV3, MasterEditionAccount,
+    Metadata, MetadataAccount, UpdateMetadataAccountsV2, VerifySizedCollectionItem,
+    mpl_token_metadata::types::{Collection, CollectionDetails, DataV2},
 };
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+
+#[cfg(feature = "cpi")]
+pub mod access;
+#[cfg(feature = "client")]
+pub mod envelope;
+#[cfg(feature = "client")]
+pub mod metadata;
 
 declare_id!("SYNiD1111111111111111111111111111111111111");
 
@@ -21,6 +37,14 @@ pub mod synid {
         config.treasury = ctx.accounts.treasury.key();
         config.paused = false;
         config.total_revenue = 0;
+        config.require_registered_requester = false;
+        config.refund_policy = RefundPolicy::None;
+        config.accepted_mints = Vec::new();
+        config.platform_fee_bps = Config::DEFAULT_PLATFORM_FEE_BPS;
+        config.fee_tiers = Vec::new();
+        config.referral_share_bps = 0;
+        config.synid_token_program = token::ID;
+        config.collection_mint = Pubkey::default();
         config.bump = ctx.bumps.config;
         Ok(())
     }
@@ -30,6 +54,9 @@ pub mod synid {
         mint_price: Option<u64>,
         access_fee: Option<u64>,
         paused: Option<bool>,
+        require_registered_requester: Option<bool>,
+        refund_policy: Option<RefundPolicy>,
+        platform_fee_bps: Option<u16>,
     ) -> Result<()> {
         let config = &mut ctx.accounts.config;
         if let Some(price) = mint_price {
@@ -41,6 +68,222 @@ pub mod synid {
         if let Some(p) = paused {
             config.paused = p;
         }
+        if let Some(required) = require_registered_requester {
+            config.require_registered_requester = required;
+        }
+        if let Some(policy) = refund_policy {
+            config.refund_policy = policy;
+        }
+        if let Some(fee_bps) = platform_fee_bps {
+            require!(fee_bps <= Config::MAX_PLATFORM_FEE_BPS, SynidError::FeeTooHigh);
+            config.platform_fee_bps = fee_bps;
+        }
+        Ok(())
+    }
+
+    pub fn set_fee_tiers(ctx: Context<UpdateConfig>, tiers: Vec<FeeTier>) -> Result<()> {
+        require!(tiers.len() <= Config::MAX_FEE_TIERS, SynidError::TooManyFeeTiers);
+        for tier in &tiers {
+            require!(tier.fee_bps <= Config::MAX_PLATFORM_FEE_BPS, SynidError::FeeTooHigh);
+        }
+
+        let config = &mut ctx.accounts.config;
+        config.fee_tiers = tiers.clone();
+
+        emit!(FeeTiersUpdated {
+            platform_fee_bps: config.platform_fee_bps,
+            tiers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_referral_share(ctx: Context<UpdateConfig>, referral_share_bps: u16) -> Result<()> {
+        require!(referral_share_bps <= 10_000, SynidError::FeeTooHigh);
+        ctx.accounts.config.referral_share_bps = referral_share_bps;
+        Ok(())
+    }
+
+    pub fn set_synid_token_program(ctx: Context<UpdateConfig>, token_program: Pubkey) -> Result<()> {
+        require!(
+            token_program == token::ID || token_program == token_2022::ID,
+            SynidError::UnsupportedTokenProgram
+        );
+        ctx.accounts.config.synid_token_program = token_program;
+        Ok(())
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let config_info = ctx.accounts.config.to_account_info();
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(data.len() >= 40, SynidError::InvalidAccountLayout);
+            require_keys_eq!(
+                Pubkey::try_from(&data[8..40]).map_err(|_| SynidError::InvalidAccountLayout)?,
+                ctx.accounts.authority.key(),
+                SynidError::Unauthorized
+            );
+        }
+        grow_account(
+            &config_info,
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            8 + Config::SIZE,
+        )?;
+
+        let mut data = config_info.try_borrow_mut_data()?;
+        let mut config = Config::try_deserialize(&mut &data[..])?;
+        config.refund_policy = RefundPolicy::None;
+        config.platform_fee_bps = Config::DEFAULT_PLATFORM_FEE_BPS;
+        config.synid_token_program = token::ID;
+        config.try_serialize(&mut &mut data[..])
+    }
+
+    pub fn migrate_synid(ctx: Context<MigrateSynid>) -> Result<()> {
+        let synid_info = ctx.accounts.synid.to_account_info();
+        grow_account(
+            &synid_info,
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            8 + SynidAccount::SIZE,
+        )?;
+
+        // Every SynID minted before the Token-2022 path existed is an SPL Token mint.
+        let mut data = synid_info.try_borrow_mut_data()?;
+        let mut synid = SynidAccount::try_deserialize(&mut &data[..])?;
+        synid.token_program = token::ID;
+        synid.try_serialize(&mut &mut data[..])
+    }
+
+    pub fn create_collection(ctx: Context<CreateCollection>, name: String, uri: String) -> Result<()> {
+        require!(name.len() <= 32, SynidError::NameTooLong);
+        require!(uri.len() <= 200, SynidError::UriTooLong);
+        require_keys_eq!(
+            ctx.accounts.config.collection_mint,
+            Pubkey::default(),
+            SynidError::CollectionAlreadyCreated
+        );
+
+        let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
+        let signer_seeds = &[&seeds[..]];
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                    to: ctx.accounts.collection_token_account.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        let data = DataV2 {
+            name,
+            symbol: "SYNID".to_string(),
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: ctx.accounts.collection_metadata.to_account_info(),
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                    mint_authority: ctx.accounts.mint_authority.to_account_info(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    update_authority: ctx.accounts.mint_authority.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            data,
+            true,
+            true,
+            Some(CollectionDetails::V1 { size: 0 }),
+        )?;
+
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.metadata_program.to_account_info(),
+                CreateMasterEditionV3 {
+                    edition: ctx.accounts.collection_master_edition.to_account_info(),
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                    update_authority: ctx.accounts.mint_authority.to_account_info(),
+                    mint_authority: ctx.accounts.mint_authority.to_account_info(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    metadata: ctx.accounts.collection_metadata.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            Some(0),
+        )?;
+
+        ctx.accounts.config.collection_mint = ctx.accounts.collection_mint.key();
+
+        emit!(CollectionCreated {
+            collection_mint: ctx.accounts.collection_mint.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_accepted_mint(ctx: Context<SetAcceptedMint>, mint_price: u64, access_fee: u64) -> Result<()> {
+        let mint = ctx.accounts.payment_mint.key();
+        let config = &mut ctx.accounts.config;
+        match config.accepted_mints.iter_mut().find(|m| m.mint == mint) {
+            Some(entry) => {
+                entry.mint_price = mint_price;
+                entry.access_fee = access_fee;
+            }
+            None => {
+                require!(config.accepted_mints.len() < Config::MAX_ACCEPTED_MINTS, SynidError::TooManyMints);
+                config.accepted_mints.push(AcceptedMint {
+                    mint,
+                    mint_price,
+                    access_fee,
+                    total_revenue: 0,
+                });
+            }
+        }
+
+        emit!(AcceptedMintUpdated {
+            mint,
+            treasury_vault: ctx.accounts.treasury_vault.key(),
+            mint_price,
+            access_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_accepted_mint(ctx: Context<UpdateConfig>, mint: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let index = config
+            .accepted_mints
+            .iter()
+            .position(|m| m.mint == mint)
+            .ok_or(SynidError::MintNotAccepted)?;
+        config.accepted_mints.remove(index);
+
+        emit!(AcceptedMintRemoved {
+            mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 
@@ -55,30 +298,40 @@ pub mod synid {
         require!(name.len() <= 32, SynidError::NameTooLong);
         require!(uri.len() <= 200, SynidError::UriTooLong);
         require!(encrypted_cid.len() <= 128, SynidError::CidTooLong);
+        require_keys_eq!(
+            ctx.accounts.config.synid_token_program,
+            token::ID,
+            SynidError::UnsupportedTokenProgram
+        );
 
-        let config = &mut ctx.accounts.config;
-        
-        if config.mint_price > 0 {
-            let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
-                &ctx.accounts.owner.key(),
-                &config.treasury,
-                config.mint_price,
+        let collection_mint = ctx.accounts.config.collection_mint;
+        let has_collection = collection_mint != Pubkey::default();
+        if has_collection {
+            require!(
+                ctx.accounts.collection_mint.is_some()
+                    && ctx.accounts.collection_metadata.is_some()
+                    && ctx.accounts.collection_master_edition.is_some(),
+                SynidError::CollectionAccountsMissing
             );
-            anchor_lang::solana_program::program::invoke(
-                &transfer_ix,
-                &[
-                    ctx.accounts.owner.to_account_info(),
-                    ctx.accounts.treasury.to_account_info(),
-                ],
-            )?;
-            config.total_revenue += config.mint_price;
         }
 
+        collect_mint_price(
+            &mut ctx.accounts.config,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.payment_mint.as_ref(),
+            ctx.accounts.payer_tokens.as_ref(),
+            ctx.accounts.treasury_tokens.as_ref(),
+            Some(ctx.accounts.token_program.to_account_info()),
+        )?;
+
+        let config = &mut ctx.accounts.config;
         config.mint_count += 1;
 
         let synid = &mut ctx.accounts.synid;
         synid.owner = ctx.accounts.owner.key();
         synid.mint = ctx.accounts.mint.key();
+        synid.token_program = token::ID;
         synid.encrypted_cid = encrypted_cid;
         synid.encryption_key_hash = encryption_key_hash;
         synid.created_at = Clock::get()?.unix_timestamp;
@@ -89,8 +342,17 @@ pub mod synid {
         synid.total_earnings = 0;
         synid.reputation_score = 100;
         synid.verified = false;
+        synid.has_field_pricing = false;
+        synid.has_requester_list = false;
         synid.bump = ctx.bumps.synid;
 
+        let earnings_vault = &mut ctx.accounts.earnings_vault;
+        earnings_vault.synid = synid.key();
+        earnings_vault.total_credited = 0;
+        earnings_vault.total_claimed = 0;
+        earnings_vault.total_refunded = 0;
+        earnings_vault.bump = ctx.bumps.earnings_vault;
+
         let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
         let signer_seeds = &[&seeds[..]];
 
@@ -107,13 +369,26 @@ pub mod synid {
             1,
         )?;
 
+        token::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::FreezeAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
         let data = DataV2 {
             name,
             symbol: "SYNID".to_string(),
             uri,
             seller_fee_basis_points: 0,
             creators: None,
-            collection: None,
+            collection: has_collection.then_some(Collection {
+                verified: false,
+                key: collection_mint,
+            }),
             uses: None,
         };
 
@@ -137,6 +412,191 @@ pub mod synid {
             None,
         )?;
 
+        if let (Some(collection_mint), Some(collection_metadata), Some(collection_master_edition)) = (
+            &ctx.accounts.collection_mint,
+            &ctx.accounts.collection_metadata,
+            &ctx.accounts.collection_master_edition,
+        ) {
+            verify_sized_collection_item(
+                CpiContext::new_with_signer(
+                    ctx.accounts.metadata_program.to_account_info(),
+                    VerifySizedCollectionItem {
+                        payer: ctx.accounts.owner.to_account_info(),
+                        metadata: ctx.accounts.metadata.to_account_info(),
+                        collection_authority: ctx.accounts.mint_authority.to_account_info(),
+                        collection_mint: collection_mint.to_account_info(),
+                        collection_metadata: collection_metadata.to_account_info(),
+                        collection_master_edition: collection_master_edition.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                None,
+            )?;
+        }
+
+        emit!(SynidMinted {
+            owner: ctx.accounts.owner.key(),
+            mint: ctx.accounts.mint.key(),
+            token_id: synid.token_id,
+            timestamp: synid.created_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn mint_synid_token2022(
+        ctx: Context<MintSynidToken2022>,
+        name: String,
+        uri: String,
+        encrypted_cid: String,
+        encryption_key_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused, SynidError::Paused);
+        require!(name.len() <= 32, SynidError::NameTooLong);
+        require!(uri.len() <= 200, SynidError::UriTooLong);
+        require!(encrypted_cid.len() <= 128, SynidError::CidTooLong);
+        require_keys_eq!(
+            ctx.accounts.config.synid_token_program,
+            token_2022::ID,
+            SynidError::UnsupportedTokenProgram
+        );
+
+        collect_mint_price(
+            &mut ctx.accounts.config,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.payment_mint.as_ref(),
+            ctx.accounts.payer_tokens.as_ref(),
+            ctx.accounts.treasury_tokens.as_ref(),
+            ctx.accounts.token_program.as_ref().map(|p| p.to_account_info()),
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        config.mint_count += 1;
+
+        let synid = &mut ctx.accounts.synid;
+        synid.owner = ctx.accounts.owner.key();
+        synid.mint = ctx.accounts.mint.key();
+        synid.token_program = token_2022::ID;
+        synid.encrypted_cid = encrypted_cid;
+        synid.encryption_key_hash = encryption_key_hash;
+        synid.created_at = Clock::get()?.unix_timestamp;
+        synid.updated_at = synid.created_at;
+        synid.token_id = config.mint_count;
+        synid.soulbound = true;
+        synid.access_count = 0;
+        synid.total_earnings = 0;
+        synid.reputation_score = 100;
+        synid.verified = false;
+        synid.has_field_pricing = false;
+        synid.has_requester_list = false;
+        synid.bump = ctx.bumps.synid;
+
+        let earnings_vault = &mut ctx.accounts.earnings_vault;
+        earnings_vault.synid = synid.key();
+        earnings_vault.total_credited = 0;
+        earnings_vault.total_claimed = 0;
+        earnings_vault.total_refunded = 0;
+        earnings_vault.bump = ctx.bumps.earnings_vault;
+
+        let mint = ctx.accounts.mint.to_account_info();
+        let mint_authority = ctx.accounts.mint_authority.to_account_info();
+        let token_2022_program = ctx.accounts.token_2022_program.to_account_info();
+        let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
+        let signer_seeds = &[&seeds[..]];
+
+        // The metadata TLV entry is appended by the token program itself, so only
+        // its rent is prepaid here; the account is allocated for the fixed extensions.
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::MetadataPointer,
+        ])?;
+        let metadata = TokenMetadata {
+            mint: mint.key(),
+            name: name.clone(),
+            symbol: "SYNID".to_string(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: mint.clone(),
+                },
+            ),
+            lamports,
+            space as u64,
+            &token_2022::ID,
+        )?;
+
+        anchor_lang::solana_program::program::invoke(
+            &spl_token_2022::instruction::initialize_non_transferable_mint(&token_2022::ID, mint.key)?,
+            std::slice::from_ref(&mint),
+        )?;
+        anchor_lang::solana_program::program::invoke(
+            &metadata_pointer::instruction::initialize(
+                &token_2022::ID,
+                mint.key,
+                Some(mint_authority.key()),
+                Some(mint.key()),
+            )?,
+            std::slice::from_ref(&mint),
+        )?;
+
+        token_2022::initialize_mint2(
+            CpiContext::new(
+                token_2022_program.clone(),
+                token_2022::InitializeMint2 { mint: mint.clone() },
+            ),
+            0,
+            mint_authority.key,
+            Some(mint_authority.key),
+        )?;
+
+        anchor_lang::solana_program::program::invoke_signed(
+            &spl_token_metadata_interface::instruction::initialize(
+                &token_2022::ID,
+                mint.key,
+                mint_authority.key,
+                mint.key,
+                mint_authority.key,
+                name,
+                "SYNID".to_string(),
+                uri,
+            ),
+            &[mint.clone(), mint_authority.clone()],
+            signer_seeds,
+        )?;
+
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.owner.to_account_info(),
+                associated_token: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+                mint: mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: token_2022_program.clone(),
+            },
+        ))?;
+
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                token_2022_program,
+                token_2022::MintTo {
+                    mint,
+                    to: ctx.accounts.token_account.to_account_info(),
+                    authority: mint_authority,
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
         emit!(SynidMinted {
             owner: ctx.accounts.owner.key(),
             mint: ctx.accounts.mint.key(),
@@ -171,83 +631,577 @@ pub mod synid {
         Ok(())
     }
 
-    pub fn request_access(
-        ctx: Context<RequestAccess>,
-        fields: Vec<String>,
-        offered_payment: u64,
-        expires_at: i64,
+    pub fn set_field_pricing(
+        ctx: Context<SetFieldPricing>,
+        prices: Vec<FieldPrice>,
+        min_payment: u64,
+        payment_mint: Option<Pubkey>,
     ) -> Result<()> {
-        require!(fields.len() <= 10, SynidError::TooManyFields);
-        require!(offered_payment >= ctx.accounts.config.access_fee, SynidError::InsufficientPayment);
+        require!(prices.len() <= FieldPricing::MAX_FIELDS, SynidError::TooManyFields);
+        for (i, entry) in prices.iter().enumerate() {
+            require!(entry.field.len() <= FieldPricing::MAX_FIELD_LEN, SynidError::FieldNameTooLong);
+            require!(
+                !prices[..i].iter().any(|p| p.field == entry.field),
+                SynidError::DuplicateField
+            );
+        }
 
-        let request = &mut ctx.accounts.access_request;
-        request.synid = ctx.accounts.synid.key();
-        request.requester = ctx.accounts.requester.key();
-        request.fields = fields.clone();
-        request.offered_payment = offered_payment;
-        request.created_at = Clock::get()?.unix_timestamp;
-        request.expires_at = expires_at;
-        request.status = AccessStatus::Pending;
-        request.bump = ctx.bumps.access_request;
+        let pricing = &mut ctx.accounts.field_pricing;
+        pricing.synid = ctx.accounts.synid.key();
+        pricing.min_payment = min_payment;
+        pricing.payment_mint = payment_mint;
+        pricing.prices = prices;
+        pricing.bump = ctx.bumps.field_pricing;
 
-        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
-            &ctx.accounts.requester.key(),
-            &ctx.accounts.escrow.key(),
-            offered_payment,
+        let synid = &mut ctx.accounts.synid;
+        synid.has_field_pricing = true;
+
+        emit!(FieldPricingUpdated {
+            synid: synid.key(),
+            owner: ctx.accounts.owner.key(),
+            min_payment,
+            payment_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_access_policy(
+        ctx: Context<SetAccessPolicy>,
+        enabled: bool,
+        fields: Vec<PolicyField>,
+        max_duration: i64,
+        allowed_requesters: Vec<Pubkey>,
+        min_requester_reputation: u16,
+        daily_cap: u32,
+    ) -> Result<()> {
+        require!(fields.len() <= AccessPolicy::MAX_FIELDS, SynidError::TooManyFields);
+        require!(
+            allowed_requesters.len() <= AccessPolicy::MAX_REQUESTERS,
+            SynidError::TooManyRequesters
         );
-        anchor_lang::solana_program::program::invoke(
-            &transfer_ix,
-            &[
-                ctx.accounts.requester.to_account_info(),
-                ctx.accounts.escrow.to_account_info(),
-            ],
-        )?;
+        for (i, entry) in fields.iter().enumerate() {
+            require!(entry.field.len() <= FieldPricing::MAX_FIELD_LEN, SynidError::FieldNameTooLong);
+            require!(
+                !fields[..i].iter().any(|f| f.field == entry.field),
+                SynidError::DuplicateField
+            );
+        }
 
-        emit!(AccessRequested {
-            synid: ctx.accounts.synid.key(),
-            requester: ctx.accounts.requester.key(),
-            fields,
-            payment: offered_payment,
-            timestamp: request.created_at,
+        let policy = &mut ctx.accounts.access_policy;
+        policy.synid = ctx.accounts.synid.key();
+        policy.enabled = enabled;
+        policy.fields = fields;
+        policy.max_duration = max_duration;
+        policy.allowed_requesters = allowed_requesters;
+        policy.min_requester_reputation = min_requester_reputation;
+        policy.daily_cap = daily_cap;
+        policy.bump = ctx.bumps.access_policy;
+
+        emit!(AccessPolicyUpdated {
+            synid: policy.synid,
+            owner: ctx.accounts.owner.key(),
+            enabled,
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    pub fn approve_access(ctx: Context<ApproveAccess>) -> Result<()> {
-        let request = &mut ctx.accounts.access_request;
-        require!(request.status == AccessStatus::Pending, SynidError::InvalidStatus);
-        require!(Clock::get()?.unix_timestamp < request.expires_at, SynidError::RequestExpired);
+    pub fn configure_requester_list(
+        ctx: Context<ConfigureRequesterList>,
+        allowlist_only: bool,
+        allowed_categories: Vec<RequesterCategory>,
+    ) -> Result<()> {
+        require!(
+            allowed_categories.len() <= RequesterList::MAX_CATEGORIES,
+            SynidError::TooManyCategories
+        );
 
-        request.status = AccessStatus::Approved;
+        let list = &mut ctx.accounts.requester_list;
+        list.synid = ctx.accounts.synid.key();
+        list.allowlist_only = allowlist_only;
+        list.allowed_categories = allowed_categories;
+        list.bump = ctx.bumps.requester_list;
 
         let synid = &mut ctx.accounts.synid;
-        synid.access_count += 1;
-        synid.total_earnings += request.offered_payment;
+        synid.has_requester_list = true;
 
-        let config = &ctx.accounts.config;
-        let platform_fee = request.offered_payment * 5 / 100;
-        let owner_payment = request.offered_payment - platform_fee;
+        emit!(RequesterListUpdated {
+            synid: list.synid,
+            allowlist_only,
+            blocked_count: list.blocked.len() as u32,
+            allowed_count: list.allowed.len() as u32,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= request.offered_payment;
-        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += owner_payment;
-        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += platform_fee;
+        Ok(())
+    }
 
-        let grant = &mut ctx.accounts.access_grant;
-        grant.synid = ctx.accounts.synid.key();
-        grant.requester = request.requester;
-        grant.fields = request.fields.clone();
-        grant.payment = request.offered_payment;
-        grant.granted_at = Clock::get()?.unix_timestamp;
-        grant.expires_at = request.expires_at;
-        grant.active = true;
-        grant.bump = ctx.bumps.access_grant;
+    pub fn add_requester_list_entries(
+        ctx: Context<UpdateRequesterList>,
+        kind: RequesterListKind,
+        entries: Vec<Pubkey>,
+    ) -> Result<()> {
+        let list = &mut ctx.accounts.requester_list;
+        let target = list.entries_mut(kind);
+        for entry in entries {
+            if !target.contains(&entry) {
+                target.push(entry);
+            }
+        }
+        require!(target.len() <= RequesterList::MAX_ENTRIES, SynidError::TooManyRequesters);
 
-        emit!(AccessApproved {
+        emit!(RequesterListUpdated {
+            synid: list.synid,
+            allowlist_only: list.allowlist_only,
+            blocked_count: list.blocked.len() as u32,
+            allowed_count: list.allowed.len() as u32,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_requester_list_entries(
+        ctx: Context<UpdateRequesterList>,
+        kind: RequesterListKind,
+        entries: Vec<Pubkey>,
+    ) -> Result<()> {
+        let list = &mut ctx.accounts.requester_list;
+        list.entries_mut(kind).retain(|entry| !entries.contains(entry));
+
+        emit!(RequesterListUpdated {
+            synid: list.synid,
+            allowlist_only: list.allowlist_only,
+            blocked_count: list.blocked.len() as u32,
+            allowed_count: list.allowed.len() as u32,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn register_requester(
+        ctx: Context<RegisterRequester>,
+        display_name: String,
+        domain: String,
+        logo_uri: String,
+        encryption_pubkey: [u8; 32],
+        category: RequesterCategory,
+    ) -> Result<()> {
+        require!(display_name.len() <= 32, SynidError::NameTooLong);
+        require!(domain.len() <= 64, SynidError::DomainTooLong);
+        require!(logo_uri.len() <= 200, SynidError::UriTooLong);
+
+        let profile = &mut ctx.accounts.requester_profile;
+        profile.authority = ctx.accounts.authority.key();
+        profile.display_name = display_name;
+        profile.domain = domain;
+        profile.logo_uri = logo_uri;
+        profile.encryption_pubkey = encryption_pubkey;
+        profile.category = category;
+        profile.verified = false;
+        profile.reputation_score = 100;
+        profile.created_at = Clock::get()?.unix_timestamp;
+        profile.updated_at = profile.created_at;
+        profile.bump = ctx.bumps.requester_profile;
+
+        emit!(RequesterRegistered {
+            authority: profile.authority,
+            category,
+            timestamp: profile.created_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_requester_profile(
+        ctx: Context<UpdateRequesterProfile>,
+        display_name: Option<String>,
+        domain: Option<String>,
+        logo_uri: Option<String>,
+        encryption_pubkey: Option<[u8; 32]>,
+        category: Option<RequesterCategory>,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.requester_profile;
+
+        if let Some(name) = display_name {
+            require!(name.len() <= 32, SynidError::NameTooLong);
+            if name != profile.display_name {
+                profile.verified = false;
+            }
+            profile.display_name = name;
+        }
+        if let Some(d) = domain {
+            require!(d.len() <= 64, SynidError::DomainTooLong);
+            if d != profile.domain {
+                profile.verified = false;
+            }
+            profile.domain = d;
+        }
+        if let Some(uri) = logo_uri {
+            require!(uri.len() <= 200, SynidError::UriTooLong);
+            profile.logo_uri = uri;
+        }
+        if let Some(key) = encryption_pubkey {
+            if key != profile.encryption_pubkey {
+                profile.verified = false;
+            }
+            profile.encryption_pubkey = key;
+        }
+        if let Some(c) = category {
+            if c != profile.category {
+                profile.verified = false;
+            }
+            profile.category = c;
+        }
+        profile.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(RequesterProfileUpdated {
+            authority: profile.authority,
+            verified: profile.verified,
+            timestamp: profile.updated_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.authority = ctx.accounts.authority.key();
+        referrer.total_earned = 0;
+        referrer.total_claimed = 0;
+        referrer.created_at = Clock::get()?.unix_timestamp;
+        referrer.bump = ctx.bumps.referrer;
+
+        emit!(ReferrerRegistered {
+            referrer: referrer.key(),
+            authority: referrer.authority,
+            timestamp: referrer.created_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_referral_earnings(ctx: Context<ClaimReferralEarnings>, amount: u64) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        require!(
+            amount <= referrer.total_earned - referrer.total_claimed,
+            SynidError::InsufficientEarnings
+        );
+
+        referrer.total_claimed += amount;
+        **referrer.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(ReferralEarningsClaimed {
+            referrer: referrer.key(),
+            authority: referrer.authority,
+            amount,
+            total_earned: referrer.total_earned,
+            total_claimed: referrer.total_claimed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn request_access(
+        ctx: Context<RequestAccess>,
+        fields: Vec<String>,
+        offered_payment: u64,
+        expires_at: i64,
+        encryption_pubkey: [u8; 32],
+        terms: AccessTerms,
+    ) -> Result<()> {
+        require!(fields.len() <= AccessRequest::MAX_FIELDS, SynidError::TooManyFields);
+        for field in &fields {
+            require!(field.len() <= FieldPricing::MAX_FIELD_LEN, SynidError::FieldNameTooLong);
+        }
+        let deposit = terms.deposit(offered_payment)?;
+        require!(
+            !ctx.accounts.config.require_registered_requester || ctx.accounts.requester_profile.is_some(),
+            SynidError::RequesterNotRegistered
+        );
+
+        if ctx.accounts.synid.has_requester_list {
+            let list = ctx
+                .accounts
+                .requester_list
+                .as_ref()
+                .ok_or(SynidError::RequesterListRequired)?;
+            list.check(&ctx.accounts.requester.key(), ctx.accounts.requester_profile.as_deref())?;
+        }
+
+        let payment_mint = ctx.accounts.payment_mint.as_ref().map(|m| m.key());
+        let referrer = match &ctx.accounts.referrer {
+            Some(referrer) => {
+                require!(referrer.authority != ctx.accounts.requester.key(), SynidError::InvalidReferrer);
+                // Referrer earnings are held in lamports, so only SOL-paid requests can carry a referral.
+                require!(payment_mint.is_none(), SynidError::ReferralUnsupportedForMint);
+                Some(referrer.key())
+            }
+            None => None,
+        };
+        let mut required_payment = match payment_mint {
+            Some(mint) => {
+                require!(terms == AccessTerms::OneTime, SynidError::UnsupportedTerms);
+                ctx.accounts.config.accepted_mint(&mint)?.access_fee
+            }
+            None => ctx.accounts.config.access_fee,
+        };
+        if ctx.accounts.synid.has_field_pricing {
+            let pricing = ctx
+                .accounts
+                .field_pricing
+                .as_ref()
+                .ok_or(SynidError::FieldPricingRequired)?;
+            require!(pricing.payment_mint == payment_mint, SynidError::PaymentMintMismatch);
+            required_payment = required_payment.max(pricing.quote(&fields)?);
+        }
+        require!(offered_payment >= required_payment, SynidError::InsufficientPayment);
+
+        let relationship = &mut ctx.accounts.relationship;
+        if relationship.next_request_id == 0 {
+            relationship.synid = ctx.accounts.synid.key();
+            relationship.requester = ctx.accounts.requester.key();
+            relationship.bump = ctx.bumps.relationship;
+        }
+        let request_id = relationship.next_request_id;
+        relationship.next_request_id += 1;
+
+        let request = &mut ctx.accounts.access_request;
+        request.synid = ctx.accounts.synid.key();
+        request.requester = ctx.accounts.requester.key();
+        request.request_id = request_id;
+        request.fields = fields.clone();
+        request.offered_payment = offered_payment;
+        request.created_at = Clock::get()?.unix_timestamp;
+        request.expires_at = expires_at;
+        request.encryption_pubkey = encryption_pubkey;
+        request.terms = terms;
+        request.payment_mint = payment_mint;
+        request.referrer = referrer;
+        request.status = AccessStatus::Pending;
+        request.counter_offer = None;
+        request.bump = ctx.bumps.access_request;
+        let created_at = request.created_at;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.access_request = request.key();
+        escrow.amount = deposit;
+        escrow.bump = ctx.bumps.escrow;
+
+        if let Some(mint) = payment_mint {
+            let requester_tokens = ctx.accounts.requester_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+            let escrow_tokens = ctx.accounts.escrow_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+            require_keys_eq!(requester_tokens.mint, mint, SynidError::PaymentMintMismatch);
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    Transfer {
+                        from: requester_tokens.to_account_info(),
+                        to: escrow_tokens.to_account_info(),
+                        authority: ctx.accounts.requester.to_account_info(),
+                    },
+                ),
+                deposit,
+            )?;
+        } else {
+            let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.requester.key(),
+                &ctx.accounts.escrow.key(),
+                deposit,
+            );
+            anchor_lang::solana_program::program::invoke(
+                &transfer_ix,
+                &[
+                    ctx.accounts.requester.to_account_info(),
+                    ctx.accounts.escrow.to_account_info(),
+                ],
+            )?;
+        }
+
+        emit!(AccessRequested {
             synid: ctx.accounts.synid.key(),
+            requester: ctx.accounts.requester.key(),
+            request_id,
+            fields,
+            payment: offered_payment,
+            payment_mint,
+            referrer,
+            timestamp: created_at,
+        });
+
+        if let Some(grant) = &ctx.accounts.access_grant {
+            if ctx.accounts.auto_approvable() {
+                ctx.accounts.auto_approve(ctx.bumps.access_grant)?;
+            } else {
+                // The policy declined: return the pre-created grant's rent and leave the request pending.
+                grant.close(ctx.accounts.requester.to_account_info())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn approve_access(ctx: Context<ApproveAccess>) -> Result<()> {
+        let fields = ctx.accounts.access_request.fields.clone();
+        ctx.accounts.approve(fields, ctx.bumps.access_grant, ctx.bumps.mint_earnings)
+    }
+
+    pub fn approve_access_partial(ctx: Context<ApproveAccess>, fields_subset: Vec<String>) -> Result<()> {
+        ctx.accounts.approve(fields_subset, ctx.bumps.access_grant, ctx.bumps.mint_earnings)
+    }
+
+    pub fn counter_access_request(
+        ctx: Context<CounterAccessRequest>,
+        payment: Option<u64>,
+        expires_at: Option<i64>,
+        fields: Option<Vec<String>>,
+    ) -> Result<()> {
+        let request = &mut ctx.accounts.access_request;
+        require!(request.is_open(), SynidError::InvalidStatus);
+        let now = Clock::get()?.unix_timestamp;
+
+        let payment = payment.unwrap_or(request.offered_payment);
+        let expires_at = expires_at.unwrap_or(request.expires_at);
+        let fields = fields.unwrap_or_else(|| request.fields.clone());
+        let access_fee = match request.payment_mint {
+            Some(mint) => ctx.accounts.config.accepted_mint(&mint)?.access_fee,
+            None => ctx.accounts.config.access_fee,
+        };
+        require!(payment >= access_fee, SynidError::InsufficientPayment);
+        require!(expires_at > now, SynidError::RequestExpired);
+        require!(!fields.is_empty(), SynidError::InvalidFieldSelection);
+        require!(fields.len() <= AccessRequest::MAX_FIELDS, SynidError::TooManyFields);
+        for (i, field) in fields.iter().enumerate() {
+            require!(field.len() <= FieldPricing::MAX_FIELD_LEN, SynidError::FieldNameTooLong);
+            require!(!fields[..i].contains(field), SynidError::DuplicateField);
+        }
+
+        request.status = AccessStatus::Countered;
+        request.counter_offer = Some(CounterOffer {
+            payment,
+            expires_at,
+            fields: fields.clone(),
+            proposed_at: now,
+        });
+
+        emit!(AccessCountered {
+            synid: request.synid,
+            requester: request.requester,
+            request_id: request.request_id,
+            payment,
+            expires_at,
+            fields,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_counter_offer(ctx: Context<AcceptCounterOffer>) -> Result<()> {
+        let request = &mut ctx.accounts.access_request;
+        require!(request.status == AccessStatus::Countered, SynidError::InvalidStatus);
+        let offer = request.counter_offer.take().ok_or(SynidError::InvalidStatus)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < offer.expires_at, SynidError::RequestExpired);
+
+        let escrow = &mut ctx.accounts.escrow;
+        let deposit = request.terms.deposit(offer.payment)?;
+        let topped_up = deposit.saturating_sub(escrow.amount);
+        let refunded = escrow.amount.saturating_sub(deposit);
+
+        if let Some(mint) = request.payment_mint {
+            let requester_tokens = ctx.accounts.requester_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+            let escrow_tokens = ctx.accounts.escrow_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+            require_keys_eq!(requester_tokens.mint, mint, SynidError::PaymentMintMismatch);
+            if topped_up > 0 {
+                token::transfer(
+                    CpiContext::new(
+                        token_program.to_account_info(),
+                        Transfer {
+                            from: requester_tokens.to_account_info(),
+                            to: escrow_tokens.to_account_info(),
+                            authority: ctx.accounts.requester.to_account_info(),
+                        },
+                    ),
+                    topped_up,
+                )?;
+            }
+            if refunded > 0 {
+                transfer_escrow_tokens(escrow, escrow_tokens, requester_tokens, token_program, refunded)?;
+            }
+        } else {
+            if topped_up > 0 {
+                let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+                    &ctx.accounts.requester.key(),
+                    &escrow.key(),
+                    topped_up,
+                );
+                anchor_lang::solana_program::program::invoke(
+                    &transfer_ix,
+                    &[
+                        ctx.accounts.requester.to_account_info(),
+                        escrow.to_account_info(),
+                    ],
+                )?;
+            }
+            if refunded > 0 {
+                **escrow.to_account_info().try_borrow_mut_lamports()? -= refunded;
+                **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += refunded;
+            }
+        }
+        escrow.amount = deposit;
+
+        request.offered_payment = offer.payment;
+        request.expires_at = offer.expires_at;
+        request.fields = offer.fields;
+        request.status = AccessStatus::Pending;
+
+        emit!(CounterOfferAccepted {
+            synid: request.synid,
+            requester: request.requester,
+            request_id: request.request_id,
+            payment: offer.payment,
+            payment_mint: request.payment_mint,
+            topped_up,
+            refunded,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn reject_counter_offer(ctx: Context<RejectCounterOffer>) -> Result<()> {
+        let request = &mut ctx.accounts.access_request;
+        require!(request.status == AccessStatus::Countered, SynidError::InvalidStatus);
+
+        request.status = AccessStatus::Rejected;
+        request.counter_offer = None;
+        if let Some(mint) = request.payment_mint {
+            refund_escrow_tokens(
+                mint,
+                &ctx.accounts.escrow,
+                ctx.accounts.escrow_tokens.as_ref(),
+                ctx.accounts.requester_tokens.as_ref(),
+                &ctx.accounts.requester.to_account_info(),
+                ctx.accounts.token_program.as_ref(),
+            )?;
+        }
+
+        emit!(CounterOfferRejected {
+            synid: request.synid,
             requester: request.requester,
-            payment: request.offered_payment,
-            timestamp: grant.granted_at,
+            request_id: request.request_id,
+            payment_mint: request.payment_mint,
+            refunded: ctx.accounts.escrow.amount,
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
@@ -255,12 +1209,19 @@ pub mod synid {
 
     pub fn deny_access(ctx: Context<DenyAccess>) -> Result<()> {
         let request = &mut ctx.accounts.access_request;
-        require!(request.status == AccessStatus::Pending, SynidError::InvalidStatus);
+        require!(request.is_open(), SynidError::InvalidStatus);
 
         request.status = AccessStatus::Denied;
-
-        **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= request.offered_payment;
-        **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += request.offered_payment;
+        if let Some(mint) = request.payment_mint {
+            refund_escrow_tokens(
+                mint,
+                &ctx.accounts.escrow,
+                ctx.accounts.escrow_tokens.as_ref(),
+                ctx.accounts.requester_tokens.as_ref(),
+                &ctx.accounts.requester.to_account_info(),
+                ctx.accounts.token_program.as_ref(),
+            )?;
+        }
 
         emit!(AccessDenied {
             synid: ctx.accounts.synid.key(),
@@ -271,93 +1232,767 @@ pub mod synid {
         Ok(())
     }
 
+    pub fn expire_access_request(ctx: Context<ExpireAccessRequest>) -> Result<()> {
+        let request = &mut ctx.accounts.access_request;
+        require!(request.is_open(), SynidError::InvalidStatus);
+        let now = Clock::get()?.unix_timestamp;
+        let deadline = request
+            .counter_offer
+            .as_ref()
+            .map_or(request.expires_at, |offer| offer.expires_at);
+        require!(now >= deadline, SynidError::RequestNotExpired);
+
+        request.status = AccessStatus::Expired;
+        if let Some(mint) = request.payment_mint {
+            refund_escrow_tokens(
+                mint,
+                &ctx.accounts.escrow,
+                ctx.accounts.escrow_tokens.as_ref(),
+                ctx.accounts.requester_tokens.as_ref(),
+                &ctx.accounts.requester.to_account_info(),
+                ctx.accounts.token_program.as_ref(),
+            )?;
+        }
+
+        emit!(AccessExpired {
+            synid: ctx.accounts.synid.key(),
+            requester: request.requester,
+            payment_mint: request.payment_mint,
+            refunded: ctx.accounts.escrow.amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_access_request(ctx: Context<CancelAccessRequest>) -> Result<()> {
+        let request = &mut ctx.accounts.access_request;
+        require!(request.is_open(), SynidError::InvalidStatus);
+        if let Some(mint) = request.payment_mint {
+            refund_escrow_tokens(
+                mint,
+                &ctx.accounts.escrow,
+                ctx.accounts.escrow_tokens.as_ref(),
+                ctx.accounts.requester_tokens.as_ref(),
+                &ctx.accounts.requester.to_account_info(),
+                ctx.accounts.token_program.as_ref(),
+            )?;
+        }
+
+        emit!(AccessRequestCancelled {
+            synid: request.synid,
+            requester: request.requester,
+            payment_mint: request.payment_mint,
+            refunded: ctx.accounts.escrow.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn extend_access_grant(
+        ctx: Context<ExtendAccessGrant>,
+        new_expires_at: i64,
+        payment: u64,
+    ) -> Result<()> {
+        let grant = &ctx.accounts.access_grant;
+        let now = Clock::get()?.unix_timestamp;
+        require!(grant.active && now < grant.expires_at, SynidError::GrantInactive);
+        require!(
+            grant.terms == AccessTerms::OneTime && grant.payment_mint.is_none(),
+            SynidError::UnsupportedTerms
+        );
+        require!(new_expires_at > grant.expires_at, SynidError::InvalidExpiry);
+
+        let extension = &mut ctx.accounts.grant_extension;
+        extension.access_grant = grant.key();
+        extension.requester = grant.requester;
+        extension.new_expires_at = new_expires_at;
+        extension.payment = payment;
+        extension.requested_at = now;
+        extension.bump = ctx.bumps.grant_extension;
+
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.requester.key(),
+            &extension.key(),
+            payment,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &transfer_ix,
+            &[
+                ctx.accounts.requester.to_account_info(),
+                extension.to_account_info(),
+            ],
+        )?;
+
+        emit!(GrantExtensionRequested {
+            synid: grant.synid,
+            requester: grant.requester,
+            access_grant: grant.key(),
+            new_expires_at,
+            payment,
+            timestamp: now,
+        });
+
+        ctx.accounts.try_auto_extend(now)
+    }
+
+    pub fn approve_grant_extension(ctx: Context<ApproveGrantExtension>) -> Result<()> {
+        let grant = &ctx.accounts.access_grant;
+        let now = Clock::get()?.unix_timestamp;
+        require!(grant.active && now < grant.expires_at, SynidError::GrantInactive);
+        let referrer = referrer_for(ctx.accounts.access_grant.referrer, ctx.accounts.referrer.as_mut())?;
+        apply_grant_extension(
+            &mut ctx.accounts.access_grant,
+            &mut ctx.accounts.synid,
+            &ctx.accounts.grant_extension,
+            &mut ctx.accounts.earnings_vault,
+            &ctx.accounts.treasury.to_account_info(),
+            referrer,
+            ctx.accounts.config.referral_share_bps,
+        )
+    }
+
+    pub fn cancel_grant_extension(ctx: Context<CancelGrantExtension>) -> Result<()> {
+        emit!(GrantExtensionCancelled {
+            synid: ctx.accounts.synid.key(),
+            requester: ctx.accounts.grant_extension.requester,
+            access_grant: ctx.accounts.access_grant.key(),
+            refunded: ctx.accounts.grant_extension.payment,
+            cancelled_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     pub fn revoke_access(ctx: Context<RevokeAccess>) -> Result<()> {
         let grant = &mut ctx.accounts.access_grant;
         require!(grant.active, SynidError::AlreadyRevoked);
+        let revoked_at = Clock::get()?.unix_timestamp;
         grant.active = false;
 
+        let pro_rata_refund = match ctx.accounts.config.refund_policy {
+            RefundPolicy::ProRata => grant.unused_payment(revoked_at),
+            RefundPolicy::None => 0,
+        };
+
+        let budget = grant.budget;
+        if budget > 0 {
+            grant.budget = 0;
+            **grant.to_account_info().try_borrow_mut_lamports()? -= budget;
+            **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += budget;
+        }
+        let refunded = pro_rata_refund + budget;
+
+        // The refund comes out of unclaimed earnings first; any part the owner has
+        // already claimed is paid from the owner's own wallet or token account.
+        if pro_rata_refund > 0 {
+            if let Some(mint) = grant.payment_mint {
+                let requester_tokens = ctx.accounts.requester_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+                let earnings_tokens = ctx.accounts.earnings_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+                let token_program = ctx.accounts.token_program.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+                let earnings = ctx.accounts.mint_earnings.as_mut().ok_or(SynidError::TokenAccountMissing)?;
+                require_keys_eq!(requester_tokens.mint, mint, SynidError::PaymentMintMismatch);
+                require_keys_eq!(earnings.mint, mint, SynidError::PaymentMintMismatch);
+
+                let from_vault = pro_rata_refund.min(earnings_tokens.amount);
+                if from_vault > 0 {
+                    let seeds = &[
+                        b"mint_earnings".as_ref(),
+                        earnings.synid.as_ref(),
+                        earnings.mint.as_ref(),
+                        &[earnings.bump],
+                    ];
+                    token::transfer(
+                        CpiContext::new_with_signer(
+                            token_program.to_account_info(),
+                            Transfer {
+                                from: earnings_tokens.to_account_info(),
+                                to: requester_tokens.to_account_info(),
+                                authority: earnings.to_account_info(),
+                            },
+                            &[&seeds[..]],
+                        ),
+                        from_vault,
+                    )?;
+                }
+                let remainder = pro_rata_refund - from_vault;
+                if remainder > 0 {
+                    let owner_tokens = ctx.accounts.owner_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+                    require_keys_eq!(owner_tokens.mint, mint, SynidError::PaymentMintMismatch);
+                    require!(owner_tokens.amount >= remainder, SynidError::InsufficientRefundFunds);
+                    token::transfer(
+                        CpiContext::new(
+                            token_program.to_account_info(),
+                            Transfer {
+                                from: owner_tokens.to_account_info(),
+                                to: requester_tokens.to_account_info(),
+                                authority: ctx.accounts.owner.to_account_info(),
+                            },
+                        ),
+                        remainder,
+                    )?;
+                }
+                earnings.total_earnings = earnings.total_earnings.saturating_sub(pro_rata_refund);
+                earnings.total_refunded += from_vault;
+            } else {
+                let vault = &mut ctx.accounts.earnings_vault;
+                let from_vault = pro_rata_refund.min(withdrawable_lamports(&vault.to_account_info())?);
+                if from_vault > 0 {
+                    **vault.to_account_info().try_borrow_mut_lamports()? -= from_vault;
+                    **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += from_vault;
+                    vault.total_refunded += from_vault;
+                }
+                let remainder = pro_rata_refund - from_vault;
+                if remainder > 0 {
+                    require!(
+                        ctx.accounts.owner.lamports() >= remainder,
+                        SynidError::InsufficientRefundFunds
+                    );
+                    let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+                        &ctx.accounts.owner.key(),
+                        &ctx.accounts.requester.key(),
+                        remainder,
+                    );
+                    anchor_lang::solana_program::program::invoke(
+                        &transfer_ix,
+                        &[
+                            ctx.accounts.owner.to_account_info(),
+                            ctx.accounts.requester.to_account_info(),
+                        ],
+                    )?;
+                }
+
+                let synid = &mut ctx.accounts.synid;
+                synid.total_earnings = synid.total_earnings.saturating_sub(pro_rata_refund);
+            }
+        }
+
         emit!(AccessRevoked {
             synid: ctx.accounts.synid.key(),
             requester: grant.requester,
-            timestamp: Clock::get()?.unix_timestamp,
+            payment_mint: grant.payment_mint,
+            refunded,
+            revoked_at,
         });
 
         Ok(())
     }
 
-    pub fn verify_identity(ctx: Context<VerifyIdentity>) -> Result<()> {
-        let synid = &mut ctx.accounts.synid;
-        synid.verified = true;
-        synid.reputation_score = synid.reputation_score.saturating_add(50);
-
-        emit!(IdentityVerified {
-            owner: synid.owner,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
+    pub fn init_earnings_vault(ctx: Context<InitEarningsVault>) -> Result<()> {
+        let earnings_vault = &mut ctx.accounts.earnings_vault;
+        earnings_vault.synid = ctx.accounts.synid.key();
+        earnings_vault.total_credited = 0;
+        earnings_vault.total_claimed = 0;
+        earnings_vault.total_refunded = 0;
+        earnings_vault.bump = ctx.bumps.earnings_vault;
         Ok(())
     }
 
-    pub fn update_reputation(ctx: Context<UpdateReputation>, delta: i16) -> Result<()> {
-        let synid = &mut ctx.accounts.synid;
-        let new_score = (synid.reputation_score as i32 + delta as i32).clamp(0, 1000) as u16;
-        synid.reputation_score = new_score;
+    pub fn claim_earnings(ctx: Context<ClaimEarnings>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.earnings_vault;
+        let available = withdrawable_lamports(&vault.to_account_info())?;
+        require!(amount <= available, SynidError::InsufficientEarnings);
 
-        emit!(ReputationUpdated {
-            owner: synid.owner,
-            new_score,
+        vault.total_claimed += amount;
+        **vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(EarningsClaimed {
+            synid: vault.synid,
+            owner: ctx.accounts.owner.key(),
+            payment_mint: None,
+            amount,
+            total_credited: vault.total_credited,
+            total_claimed: vault.total_claimed,
+            available: available - amount,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    pub fn burn_synid(ctx: Context<BurnSynid>) -> Result<()> {
-        let synid = &ctx.accounts.synid;
-
-        let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
-        let signer_seeds = &[&seeds[..]];
+    pub fn claim_token_earnings(ctx: Context<ClaimTokenEarnings>, amount: u64) -> Result<()> {
+        let earnings = &mut ctx.accounts.mint_earnings;
+        let available = ctx.accounts.earnings_tokens.amount;
+        require!(amount <= available, SynidError::InsufficientEarnings);
 
-        token::burn(
+        let seeds = &[
+            b"mint_earnings".as_ref(),
+            earnings.synid.as_ref(),
+            earnings.mint.as_ref(),
+            &[earnings.bump],
+        ];
+        token::transfer(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    from: ctx.accounts.token_account.to_account_info(),
-                    authority: ctx.accounts.owner.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.earnings_tokens.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: earnings.to_account_info(),
                 },
-                signer_seeds,
+                &[&seeds[..]],
             ),
-            1,
+            amount,
         )?;
+        earnings.total_claimed += amount;
 
-        emit!(SynidBurned {
+        emit!(EarningsClaimed {
+            synid: earnings.synid,
             owner: ctx.accounts.owner.key(),
-            token_id: synid.token_id,
+            payment_mint: Some(earnings.mint),
+            amount,
+            total_credited: earnings.total_credited,
+            total_claimed: earnings.total_claimed,
+            available: available - amount,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
-        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+    pub fn close_access_request(ctx: Context<CloseAccessRequest>) -> Result<()> {
+        let request = &ctx.accounts.access_request;
+        require!(
+            matches!(
+                request.status,
+                AccessStatus::Approved | AccessStatus::Denied | AccessStatus::Rejected
+            ),
+            SynidError::InvalidStatus
+        );
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Config::SIZE,
-        seeds = [b"config"],
-        bump
-    )]
-    pub config: Account<'info, Config>,
-    #[account(mut)]
+    pub fn close_access_grant(ctx: Context<CloseAccessGrant>) -> Result<()> {
+        let grant = &ctx.accounts.access_grant;
+        require!(
+            !grant.active || Clock::get()?.unix_timestamp >= grant.expires_at,
+            SynidError::GrantStillActive
+        );
+        require!(grant.budget == 0, SynidError::BudgetRemaining);
+        Ok(())
+    }
+
+    pub fn settle_subscription_period(ctx: Context<SettleSubscriptionPeriod>) -> Result<()> {
+        let grant = &mut ctx.accounts.access_grant;
+        require!(grant.active, SynidError::GrantInactive);
+        let AccessTerms::Subscription { period, .. } = grant.terms else {
+            return err!(SynidError::UnsupportedTerms);
+        };
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= grant.expires_at, SynidError::PeriodNotElapsed);
+
+        if grant.budget < grant.price {
+            let refunded = grant.budget;
+            grant.budget = 0;
+            grant.active = false;
+            **grant.to_account_info().try_borrow_mut_lamports()? -= refunded;
+            **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += refunded;
+
+            emit!(SubscriptionLapsed {
+                synid: grant.synid,
+                requester: grant.requester,
+                access_grant: grant.key(),
+                refunded,
+                timestamp: now,
+            });
+            return Ok(());
+        }
+
+        let price = grant.price;
+        let (owner_payment, platform_fee) = settle_payment(
+            &grant.to_account_info(),
+            &mut ctx.accounts.earnings_vault,
+            &ctx.accounts.treasury.to_account_info(),
+            referrer_for(grant.referrer, ctx.accounts.referrer.as_mut())?,
+            price,
+            grant.fee_bps,
+            ctx.accounts.config.referral_share_bps,
+        )?;
+        grant.budget -= price;
+        grant.payment += price;
+        grant.owner_payment = owner_payment;
+        grant.term_start = grant.expires_at;
+        grant.expires_at += period;
+
+        let synid = &mut ctx.accounts.synid;
+        synid.total_earnings += price;
+
+        emit!(SubscriptionSettled {
+            synid: grant.synid,
+            requester: grant.requester,
+            access_grant: grant.key(),
+            payment: price,
+            platform_fee,
+            remaining_budget: grant.budget,
+            period_ends_at: grant.expires_at,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn record_access_read(ctx: Context<RecordAccessRead>, data_version: Option<[u8; 32]>) -> Result<()> {
+        let grant = &mut ctx.accounts.access_grant;
+        let now = Clock::get()?.unix_timestamp;
+        require!(grant.active && now < grant.expires_at, SynidError::GrantInactive);
+
+        let price = match grant.terms {
+            AccessTerms::Metered { .. } => grant.price,
+            _ => 0,
+        };
+        let mut platform_fee = 0;
+        if price > 0 {
+            require!(grant.budget >= price, SynidError::BudgetExhausted);
+            let (owner_payment, fee) = settle_payment(
+                &grant.to_account_info(),
+                &mut ctx.accounts.earnings_vault,
+                &ctx.accounts.treasury.to_account_info(),
+                referrer_for(grant.referrer, ctx.accounts.referrer.as_mut())?,
+                price,
+                grant.fee_bps,
+                ctx.accounts.config.referral_share_bps,
+            )?;
+            platform_fee = fee;
+            grant.budget -= price;
+            grant.payment += price;
+            grant.owner_payment += owner_payment;
+        }
+        grant.read_count += 1;
+
+        let synid = &mut ctx.accounts.synid;
+        synid.access_count += 1;
+        synid.total_earnings += price;
+
+        emit!(AccessRead {
+            synid: grant.synid,
+            requester: grant.requester,
+            access_grant: grant.key(),
+            price,
+            platform_fee,
+            data_version,
+            remaining_budget: grant.budget,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn reclaim_grant_budget(ctx: Context<ReclaimGrantBudget>) -> Result<()> {
+        let grant = &mut ctx.accounts.access_grant;
+        let now = Clock::get()?.unix_timestamp;
+        require!(!grant.active || now >= grant.expires_at, SynidError::GrantStillActive);
+
+        let refunded = grant.budget;
+        grant.budget = 0;
+        **grant.to_account_info().try_borrow_mut_lamports()? -= refunded;
+        **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += refunded;
+
+        emit!(GrantBudgetReclaimed {
+            synid: grant.synid,
+            requester: grant.requester,
+            access_grant: grant.key(),
+            refunded,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        let grant = &mut ctx.accounts.access_grant;
+        require!(
+            matches!(grant.terms, AccessTerms::Subscription { .. }),
+            SynidError::UnsupportedTerms
+        );
+        require!(grant.active, SynidError::GrantInactive);
+
+        let refunded = grant.budget;
+        grant.budget = 0;
+        **grant.to_account_info().try_borrow_mut_lamports()? -= refunded;
+        **ctx.accounts.requester.to_account_info().try_borrow_mut_lamports()? += refunded;
+
+        emit!(SubscriptionCancelled {
+            synid: grant.synid,
+            requester: grant.requester,
+            access_grant: grant.key(),
+            refunded,
+            cancelled_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn deliver_field_keys(
+        ctx: Context<DeliverFieldKeys>,
+        ephemeral_pubkey: [u8; 32],
+        keys: Vec<WrappedFieldKey>,
+    ) -> Result<()> {
+        let grant = &ctx.accounts.access_grant;
+        let now = Clock::get()?.unix_timestamp;
+        require!(grant.active && now < grant.expires_at, SynidError::GrantInactive);
+        require!(!keys.is_empty(), SynidError::InvalidFieldSelection);
+        require!(keys.len() <= KeyEnvelope::MAX_KEYS, SynidError::TooManyFields);
+        for (i, key) in keys.iter().enumerate() {
+            require!(key.field.len() <= FieldPricing::MAX_FIELD_LEN, SynidError::FieldNameTooLong);
+            require!(grant.fields.contains(&key.field), SynidError::InvalidFieldSelection);
+            require!(
+                !keys[..i].iter().any(|k| k.field == key.field),
+                SynidError::DuplicateField
+            );
+        }
+
+        let envelope = &mut ctx.accounts.key_envelope;
+        envelope.access_grant = grant.key();
+        envelope.requester = grant.requester;
+        envelope.recipient_pubkey = grant.encryption_pubkey;
+        envelope.ephemeral_pubkey = ephemeral_pubkey;
+        envelope.keys = keys;
+        envelope.delivered_at = now;
+        envelope.bump = ctx.bumps.key_envelope;
+
+        emit!(FieldKeysDelivered {
+            synid: grant.synid,
+            requester: grant.requester,
+            access_grant: envelope.access_grant,
+            field_count: envelope.keys.len() as u32,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn check_access(
+        ctx: Context<CheckAccess>,
+        requester: Pubkey,
+        fields: Vec<String>,
+    ) -> Result<AccessCheck> {
+        let grant = &ctx.accounts.access_grant;
+        let now = Clock::get()?.unix_timestamp;
+
+        Ok(AccessCheck {
+            status: grant.access_status(&requester, &fields, now),
+            granted_at: grant.granted_at,
+            expires_at: grant.expires_at,
+        })
+    }
+
+    pub fn verify_identity(ctx: Context<VerifyIdentity>, attributes_uri: Option<String>) -> Result<()> {
+        let synid = &mut ctx.accounts.synid;
+        synid.verified = true;
+        synid.reputation_score = synid.reputation_score.saturating_add(50);
+
+        if let Some(uri) = &attributes_uri {
+            set_attributes_uri(
+                ctx.accounts.mint.as_ref(),
+                ctx.accounts.mint_authority.as_ref(),
+                ctx.bumps.mint_authority,
+                ctx.accounts.token_2022_program.as_ref(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.system_program.as_ref(),
+                uri.clone(),
+            )?;
+        }
+
+        emit!(IdentityVerified {
+            owner: ctx.accounts.synid.owner,
+            attributes_uri,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_reputation(
+        ctx: Context<UpdateReputation>,
+        delta: i16,
+        attributes_uri: Option<String>,
+    ) -> Result<()> {
+        let synid = &mut ctx.accounts.synid;
+        let new_score = (synid.reputation_score as i32 + delta as i32).clamp(0, 1000) as u16;
+        synid.reputation_score = new_score;
+
+        if let Some(uri) = &attributes_uri {
+            set_attributes_uri(
+                ctx.accounts.mint.as_ref(),
+                ctx.accounts.mint_authority.as_ref(),
+                ctx.bumps.mint_authority,
+                ctx.accounts.token_2022_program.as_ref(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.system_program.as_ref(),
+                uri.clone(),
+            )?;
+        }
+
+        emit!(ReputationUpdated {
+            owner: ctx.accounts.synid.owner,
+            new_score,
+            attributes_uri,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn verify_requester(ctx: Context<VerifyRequester>, verified: bool) -> Result<()> {
+        let profile = &mut ctx.accounts.requester_profile;
+        profile.verified = verified;
+
+        emit!(RequesterVerified {
+            authority: profile.authority,
+            verified,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_requester_reputation(ctx: Context<VerifyRequester>, delta: i16) -> Result<()> {
+        let profile = &mut ctx.accounts.requester_profile;
+        let new_score = (profile.reputation_score as i32 + delta as i32).clamp(0, 1000) as u16;
+        profile.reputation_score = new_score;
+
+        emit!(RequesterReputationUpdated {
+            authority: profile.authority,
+            new_score,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_synid_metadata(ctx: Context<UpdateSynidMetadata>, name: String, uri: String) -> Result<()> {
+        require!(name.len() <= 32, SynidError::NameTooLong);
+        require!(uri.len() <= 200, SynidError::UriTooLong);
+
+        let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
+        let signer_seeds = &[&seeds[..]];
+
+        if ctx.accounts.synid.token_program == token_2022::ID {
+            let mint = ctx.accounts.mint.as_ref().ok_or(SynidError::MetadataAccountsMissing)?;
+            let token_2022_program = ctx
+                .accounts
+                .token_2022_program
+                .as_ref()
+                .ok_or(SynidError::MetadataAccountsMissing)?;
+            update_token_2022_metadata(
+                token_2022_program.to_account_info(),
+                mint.to_account_info(),
+                ctx.accounts.mint_authority.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                signer_seeds,
+                vec![(Field::Name, name.clone()), (Field::Uri, uri.clone())],
+            )?;
+        } else {
+            let metadata = ctx.accounts.metadata.as_ref().ok_or(SynidError::MetadataAccountsMissing)?;
+            let metadata_program = ctx
+                .accounts
+                .metadata_program
+                .as_ref()
+                .ok_or(SynidError::MetadataAccountsMissing)?;
+            update_metaplex_metadata(
+                metadata_program.to_account_info(),
+                metadata,
+                ctx.accounts.mint_authority.to_account_info(),
+                signer_seeds,
+                Some(name.clone()),
+                uri.clone(),
+            )?;
+        }
+
+        let synid = &mut ctx.accounts.synid;
+        synid.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(SynidMetadataUpdated {
+            owner: synid.owner,
+            mint: synid.mint,
+            name,
+            uri,
+            timestamp: synid.updated_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn burn_synid(ctx: Context<BurnSynid>) -> Result<()> {
+        let synid = &ctx.accounts.synid;
+
+        let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
+        let signer_seeds = &[&seeds[..]];
+
+        if ctx.accounts.token_account.is_frozen() {
+            token_interface::thaw_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::ThawAccount {
+                    account: ctx.accounts.token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.token_account.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        emit!(SynidBurned {
+            owner: ctx.accounts.owner.key(),
+            token_id: synid.token_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    pub fn withdraw_treasury_tokens(ctx: Context<WithdrawTreasuryTokens>, amount: u64) -> Result<()> {
+        let seeds = &[b"config".as_ref(), &[ctx.accounts.config.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury_vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.config.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Config::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
     pub authority: Signer<'info>,
     pub treasury: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
@@ -370,6 +2005,82 @@ pub struct UpdateConfig<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: deserialized as `Config` after it has been resized to the current layout.
+    #[account(mut, owner = crate::ID, seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateSynid<'info> {
+    /// CHECK: deserialized as `SynidAccount` after it has been resized to the current layout.
+    #[account(mut, owner = crate::ID)]
+    pub synid: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCollection<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = mint_authority,
+        mint::freeze_authority = mint_authority,
+        seeds = [b"collection_mint"],
+        bump
+    )]
+    pub collection_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = collection_mint,
+        associated_token::authority = mint_authority,
+    )]
+    pub collection_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: SystemAccount<'info>,
+    #[account(mut)]
+    pub collection_metadata: SystemAccount<'info>,
+    #[account(mut)]
+    pub collection_master_edition: SystemAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetAcceptedMint<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    pub payment_mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = payment_mint,
+        token::authority = config,
+        seeds = [b"treasury_vault", payment_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct MintSynid<'info> {
     #[account(mut, seeds = [b"config"], bump = config.bump)]
@@ -382,6 +2093,14 @@ pub struct MintSynid<'info> {
         bump
     )]
     pub synid: Account<'info, SynidAccount>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + EarningsVault::SIZE,
+        seeds = [b"earnings_vault", synid.key().as_ref()],
+        bump
+    )]
+    pub earnings_vault: Account<'info, EarningsVault>,
     #[account(
         init,
         payer = owner,
@@ -405,6 +2124,16 @@ pub struct MintSynid<'info> {
     pub owner: Signer<'info>,
     #[account(mut)]
     pub treasury: SystemAccount<'info>,
+    pub payment_mint: Option<Account<'info, Mint>>,
+    #[account(mut, token::authority = owner)]
+    pub payer_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = config)]
+    pub treasury_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(address = config.collection_mint)]
+    pub collection_mint: Option<Account<'info, Mint>>,
+    #[account(mut)]
+    pub collection_metadata: Option<Account<'info, MetadataAccount>>,
+    pub collection_master_edition: Option<Account<'info, MasterEditionAccount>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub metadata_program: Program<'info, Metadata>,
@@ -412,6 +2141,47 @@ pub struct MintSynid<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct MintSynidToken2022<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + SynidAccount::SIZE,
+        seeds = [b"synid", owner.key().as_ref()],
+        bump
+    )]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + EarningsVault::SIZE,
+        seeds = [b"earnings_vault", synid.key().as_ref()],
+        bump
+    )]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut)]
+    pub mint: Signer<'info>,
+    #[account(mut)]
+    pub token_account: SystemAccount<'info>,
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+    pub payment_mint: Option<Account<'info, Mint>>,
+    #[account(mut, token::authority = owner)]
+    pub payer_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = config)]
+    pub treasury_tokens: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct UpdateProfile<'info> {
     #[account(mut, seeds = [b"synid", owner.key().as_ref()], bump = synid.bump, has_one = owner)]
@@ -419,228 +2189,2012 @@ pub struct UpdateProfile<'info> {
     pub owner: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SetFieldPricing<'info> {
+    #[account(mut, seeds = [b"synid", owner.key().as_ref()], bump = synid.bump, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + FieldPricing::SIZE,
+        seeds = [b"field_pricing", synid.key().as_ref()],
+        bump
+    )]
+    pub field_pricing: Account<'info, FieldPricing>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccessPolicy<'info> {
+    #[account(seeds = [b"synid", owner.key().as_ref()], bump = synid.bump, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AccessPolicy::SIZE,
+        seeds = [b"access_policy", synid.key().as_ref()],
+        bump
+    )]
+    pub access_policy: Account<'info, AccessPolicy>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureRequesterList<'info> {
+    #[account(mut, seeds = [b"synid", owner.key().as_ref()], bump = synid.bump, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + RequesterList::SIZE,
+        seeds = [b"requester_list", synid.key().as_ref()],
+        bump
+    )]
+    pub requester_list: Account<'info, RequesterList>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRequesterList<'info> {
+    #[account(seeds = [b"synid", owner.key().as_ref()], bump = synid.bump, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        mut,
+        seeds = [b"requester_list", synid.key().as_ref()],
+        bump = requester_list.bump,
+        has_one = synid
+    )]
+    pub requester_list: Account<'info, RequesterList>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterRequester<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RequesterProfile::SIZE,
+        seeds = [b"requester", authority.key().as_ref()],
+        bump
+    )]
+    pub requester_profile: Account<'info, RequesterProfile>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRequesterProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"requester", authority.key().as_ref()],
+        bump = requester_profile.bump,
+        has_one = authority
+    )]
+    pub requester_profile: Account<'info, RequesterProfile>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(seeds = [b"requester", authority.key().as_ref()], bump = requester_profile.bump, has_one = authority)]
+    pub requester_profile: Account<'info, RequesterProfile>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Referrer::SIZE,
+        seeds = [b"referrer", authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralEarnings<'info> {
+    #[account(mut, seeds = [b"referrer", authority.key().as_ref()], bump = referrer.bump, has_one = authority)]
+    pub referrer: Account<'info, Referrer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct RequestAccess<'info> {
     #[account(seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, Config>,
+    #[account(mut)]
     pub synid: Account<'info, SynidAccount>,
+    #[account(seeds = [b"field_pricing", synid.key().as_ref()], bump = field_pricing.bump)]
+    pub field_pricing: Option<Account<'info, FieldPricing>>,
+    #[account(seeds = [b"requester_list", synid.key().as_ref()], bump = requester_list.bump)]
+    pub requester_list: Option<Account<'info, RequesterList>>,
+    #[account(mut, seeds = [b"access_policy", synid.key().as_ref()], bump = access_policy.bump)]
+    pub access_policy: Option<Account<'info, AccessPolicy>>,
+    #[account(seeds = [b"requester", requester.key().as_ref()], bump = requester_profile.bump)]
+    pub requester_profile: Option<Account<'info, RequesterProfile>>,
+    #[account(
+        init_if_needed,
+        payer = requester,
+        space = 8 + AccessRelationship::SIZE,
+        seeds = [b"relationship", synid.key().as_ref(), requester.key().as_ref()],
+        bump
+    )]
+    pub relationship: Account<'info, AccessRelationship>,
     #[account(
         init,
         payer = requester,
         space = 8 + AccessRequest::SIZE,
-        seeds = [b"access_request", synid.key().as_ref(), requester.key().as_ref()],
+        seeds = [
+            b"access_request",
+            synid.key().as_ref(),
+            requester.key().as_ref(),
+            relationship.next_request_id.to_le_bytes().as_ref(),
+        ],
         bump
     )]
     pub access_request: Account<'info, AccessRequest>,
-    #[account(mut, seeds = [b"escrow"], bump)]
-    pub escrow: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + EscrowVault::SIZE,
+        seeds = [b"escrow", access_request.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + AccessGrant::SIZE,
+        seeds = [
+            b"access_grant",
+            synid.key().as_ref(),
+            requester.key().as_ref(),
+            relationship.next_request_id.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub access_grant: Option<Account<'info, AccessGrant>>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Option<Account<'info, EarningsVault>>,
+    #[account(mut, address = config.treasury)]
+    pub treasury: Option<SystemAccount<'info>>,
+    pub payment_mint: Option<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = requester,
+        token::mint = payment_mint,
+        token::authority = escrow,
+        seeds = [b"escrow_tokens", access_request.key().as_ref()],
+        bump
+    )]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
     #[account(mut)]
     pub requester: Signer<'info>,
+    pub token_program: Option<Program<'info, Token>>,
     pub system_program: Program<'info, System>,
 }
 
+impl<'info> RequestAccess<'info> {
+    fn auto_approvable(&self) -> bool {
+        let request = &self.access_request;
+        let Some(policy) = self.access_policy.as_ref() else {
+            return false;
+        };
+        request.terms == AccessTerms::OneTime
+            && request.payment_mint.is_none()
+            && policy
+                .check(
+                    &request.requester,
+                    &request.fields,
+                    request.offered_payment,
+                    request.expires_at - request.created_at,
+                    self.requester_profile.as_ref().map(|p| p.reputation_score),
+                    request.created_at,
+                )
+                .is_ok()
+    }
+
+    fn auto_approve(&mut self, grant_bump: u8) -> Result<()> {
+        let request = &mut self.access_request;
+        let now = request.created_at;
+        require!(
+            request.terms == AccessTerms::OneTime && request.payment_mint.is_none(),
+            SynidError::PolicyNotSatisfied
+        );
+        let requester_reputation = self.requester_profile.as_ref().map(|p| p.reputation_score);
+        let policy = self.access_policy.as_mut().ok_or(SynidError::PolicyNotSatisfied)?;
+        policy.authorize(
+            &request.requester,
+            &request.fields,
+            request.offered_payment,
+            request.expires_at - now,
+            requester_reputation,
+            now,
+        )?;
+
+        let earnings_vault = self.earnings_vault.as_mut().ok_or(SynidError::SettlementAccountMissing)?;
+        let treasury = self.treasury.as_ref().ok_or(SynidError::SettlementAccountMissing)?;
+        let payment = self.escrow.amount;
+        let fee_bps = self
+            .config
+            .fee_bps(self.requester_profile.as_deref(), self.synid.reputation_score);
+        let (owner_payment, platform_fee) = settle_payment(
+            &self.escrow.to_account_info(),
+            earnings_vault,
+            &treasury.to_account_info(),
+            referrer_for(request.referrer, self.referrer.as_mut())?,
+            payment,
+            fee_bps,
+            self.config.referral_share_bps,
+        )?;
+        self.escrow.close(self.requester.to_account_info())?;
+
+        request.status = AccessStatus::Approved;
+
+        let synid = &mut self.synid;
+        synid.total_earnings += payment;
+
+        let grant = self.access_grant.as_mut().ok_or(SynidError::PolicyNotSatisfied)?;
+        grant.synid = synid.key();
+        grant.requester = request.requester;
+        grant.request_id = request.request_id;
+        grant.encryption_pubkey = request.encryption_pubkey;
+        grant.requested_fields = request.fields.clone();
+        grant.fields = request.fields.clone();
+        grant.terms = AccessTerms::OneTime;
+        grant.payment_mint = None;
+        grant.fee_bps = fee_bps;
+        grant.referrer = request.referrer;
+        grant.price = payment;
+        grant.payment = payment;
+        grant.owner_payment = owner_payment;
+        grant.budget = 0;
+        grant.read_count = 0;
+        grant.granted_at = now;
+        grant.term_start = now;
+        grant.expires_at = request.expires_at;
+        grant.active = true;
+        grant.bump = grant_bump;
+        grant.rent_payer = request.requester;
+
+        emit!(AccessApproved {
+            synid: grant.synid,
+            requester: request.requester,
+            requested_fields: grant.requested_fields.clone(),
+            granted_fields: grant.fields.clone(),
+            payment,
+            payment_mint: None,
+            platform_fee,
+            refunded: 0,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 pub struct ApproveAccess<'info> {
     #[account(seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, Config>,
     #[account(mut, has_one = owner)]
     pub synid: Account<'info, SynidAccount>,
+    #[account(seeds = [b"field_pricing", synid.key().as_ref()], bump = field_pricing.bump)]
+    pub field_pricing: Option<Account<'info, FieldPricing>>,
     #[account(mut, has_one = synid)]
     pub access_request: Account<'info, AccessRequest>,
+    #[account(
+        seeds = [b"requester", access_request.requester.as_ref()],
+        bump = requester_profile.bump
+    )]
+    pub requester_profile: Option<Account<'info, RequesterProfile>>,
     #[account(
         init,
         payer = owner,
         space = 8 + AccessGrant::SIZE,
-        seeds = [b"access_grant", synid.key().as_ref(), access_request.requester.as_ref()],
+        seeds = [
+            b"access_grant",
+            synid.key().as_ref(),
+            access_request.requester.as_ref(),
+            access_request.request_id.to_le_bytes().as_ref(),
+        ],
         bump
     )]
     pub access_grant: Account<'info, AccessGrant>,
-    #[account(mut, seeds = [b"escrow"], bump)]
-    pub escrow: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", access_request.key().as_ref()],
+        bump = escrow.bump,
+        close = requester
+    )]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(mut, address = access_request.requester)]
+    pub requester: SystemAccount<'info>,
     #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(mut)]
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut, address = config.treasury)]
     pub treasury: SystemAccount<'info>,
+    pub payment_mint: Option<Account<'info, Mint>>,
+    #[account(mut, seeds = [b"escrow_tokens", access_request.key().as_ref()], bump)]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = config)]
+    pub treasury_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + MintEarnings::SIZE,
+        seeds = [
+            b"mint_earnings",
+            synid.key().as_ref(),
+            access_request.payment_mint.unwrap_or_default().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_earnings: Option<Account<'info, MintEarnings>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        token::mint = payment_mint,
+        token::authority = mint_earnings,
+        seeds = [
+            b"earnings_tokens",
+            synid.key().as_ref(),
+            access_request.payment_mint.unwrap_or_default().as_ref(),
+        ],
+        bump
+    )]
+    pub earnings_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
+    pub token_program: Option<Program<'info, Token>>,
     pub system_program: Program<'info, System>,
 }
 
+impl<'info> ApproveAccess<'info> {
+    fn approve(&mut self, granted_fields: Vec<String>, grant_bump: u8, earnings_bump: u8) -> Result<()> {
+        let request = &mut self.access_request;
+        require!(request.status == AccessStatus::Pending, SynidError::InvalidStatus);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < request.expires_at, SynidError::RequestExpired);
+        require!(!granted_fields.is_empty(), SynidError::InvalidFieldSelection);
+        for (i, field) in granted_fields.iter().enumerate() {
+            require!(request.fields.contains(field), SynidError::InvalidFieldSelection);
+            require!(!granted_fields[..i].contains(field), SynidError::DuplicateField);
+        }
+
+        let escrowed = self.escrow.amount;
+        let payment = if granted_fields.len() == request.fields.len() {
+            escrowed
+        } else {
+            require!(request.terms == AccessTerms::OneTime, SynidError::UnsupportedTerms);
+            let (granted_weight, requested_weight) =
+                match (&self.field_pricing, self.synid.has_field_pricing) {
+                    (Some(pricing), true) => (
+                        pricing.price_of(&granted_fields)?,
+                        pricing.price_of(&request.fields)?,
+                    ),
+                    (None, true) => return err!(SynidError::FieldPricingRequired),
+                    _ => (0, 0),
+                };
+            partial_payment(
+                escrowed,
+                (granted_weight, granted_fields.len()),
+                (requested_weight, request.fields.len()),
+            )
+        };
+        let (payment, budget, expires_at) = match request.terms {
+            AccessTerms::OneTime => (payment, 0, request.expires_at),
+            AccessTerms::Subscription { period, .. } => {
+                (request.offered_payment, escrowed - request.offered_payment, now + period)
+            }
+            AccessTerms::Metered { .. } => (0, escrowed, request.expires_at),
+        };
+        let refunded = escrowed - payment - budget;
+
+        request.status = AccessStatus::Approved;
+        let payment_mint = request.payment_mint;
+        let fee_bps = self
+            .config
+            .fee_bps(self.requester_profile.as_deref(), self.synid.reputation_score);
+
+        let (owner_payment, platform_fee) = match payment_mint {
+            Some(mint) => self.settle_tokens(mint, payment, refunded, fee_bps, earnings_bump)?,
+            None => {
+                let settled = settle_payment(
+                    &self.escrow.to_account_info(),
+                    &mut self.earnings_vault,
+                    &self.treasury.to_account_info(),
+                    referrer_for(self.access_request.referrer, self.referrer.as_mut())?,
+                    payment,
+                    fee_bps,
+                    self.config.referral_share_bps,
+                )?;
+                if budget > 0 {
+                    **self.escrow.to_account_info().try_borrow_mut_lamports()? -= budget;
+                    **self.access_grant.to_account_info().try_borrow_mut_lamports()? += budget;
+                }
+                self.synid.total_earnings += payment;
+                settled
+            }
+        };
+
+        let request = &self.access_request;
+        let grant = &mut self.access_grant;
+        grant.synid = self.synid.key();
+        grant.requester = request.requester;
+        grant.request_id = request.request_id;
+        grant.encryption_pubkey = request.encryption_pubkey;
+        grant.requested_fields = request.fields.clone();
+        grant.fields = granted_fields;
+        grant.terms = request.terms;
+        grant.payment_mint = payment_mint;
+        grant.fee_bps = fee_bps;
+        grant.referrer = request.referrer;
+        grant.price = request.offered_payment;
+        grant.payment = payment;
+        grant.owner_payment = owner_payment;
+        grant.budget = budget;
+        grant.read_count = 0;
+        grant.granted_at = now;
+        grant.term_start = now;
+        grant.expires_at = expires_at;
+        grant.active = true;
+        grant.bump = grant_bump;
+        grant.rent_payer = self.owner.key();
+
+        emit!(AccessApproved {
+            synid: grant.synid,
+            requester: request.requester,
+            requested_fields: grant.requested_fields.clone(),
+            granted_fields: grant.fields.clone(),
+            payment,
+            payment_mint,
+            platform_fee,
+            refunded,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    fn settle_tokens(
+        &mut self,
+        mint: Pubkey,
+        payment: u64,
+        refunded: u64,
+        fee_bps: u16,
+        earnings_bump: u8,
+    ) -> Result<(u64, u64)> {
+        let payment_mint = self.payment_mint.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+        let escrow_tokens = self.escrow_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+        let earnings_tokens = self.earnings_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+        let treasury_tokens = self.treasury_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+        let requester_tokens = self.requester_tokens.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+        let token_program = self.token_program.as_ref().ok_or(SynidError::TokenAccountMissing)?;
+        require_keys_eq!(payment_mint.key(), mint, SynidError::PaymentMintMismatch);
+        require_keys_eq!(treasury_tokens.mint, mint, SynidError::PaymentMintMismatch);
+        require_keys_eq!(requester_tokens.mint, mint, SynidError::PaymentMintMismatch);
+
+        let platform_fee = platform_fee(payment, fee_bps);
+        let owner_payment = payment - platform_fee;
+        transfer_escrow_tokens(&self.escrow, escrow_tokens, earnings_tokens, token_program, owner_payment)?;
+        transfer_escrow_tokens(&self.escrow, escrow_tokens, treasury_tokens, token_program, platform_fee)?;
+        if refunded > 0 {
+            transfer_escrow_tokens(&self.escrow, escrow_tokens, requester_tokens, token_program, refunded)?;
+        }
+        close_escrow_tokens(&self.escrow, escrow_tokens, &self.requester.to_account_info(), token_program)?;
+
+        let earnings = self.mint_earnings.as_mut().ok_or(SynidError::TokenAccountMissing)?;
+        if earnings.synid == Pubkey::default() {
+            earnings.synid = self.synid.key();
+            earnings.mint = mint;
+            earnings.bump = earnings_bump;
+        }
+        earnings.total_earnings += payment;
+        earnings.total_credited += owner_payment;
+
+        Ok((owner_payment, platform_fee))
+    }
+}
+
 #[derive(Accounts)]
-pub struct DenyAccess<'info> {
+pub struct CounterAccessRequest<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(has_one = owner)]
     pub synid: Account<'info, SynidAccount>,
     #[account(mut, has_one = synid)]
     pub access_request: Account<'info, AccessRequest>,
-    #[account(mut, seeds = [b"escrow"], bump)]
-    pub escrow: SystemAccount<'info>,
-    #[account(mut)]
-    pub requester: SystemAccount<'info>,
     pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct RevokeAccess<'info> {
-    #[account(has_one = owner)]
-    pub synid: Account<'info, SynidAccount>,
+pub struct AcceptCounterOffer<'info> {
+    #[account(mut, has_one = requester)]
+    pub access_request: Account<'info, AccessRequest>,
+    #[account(mut, seeds = [b"escrow", access_request.key().as_ref()], bump = escrow.bump)]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(mut, seeds = [b"escrow_tokens", access_request.key().as_ref()], bump)]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub access_grant: Account<'info, AccessGrant>,
-    pub owner: Signer<'info>,
+    pub requester: Signer<'info>,
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct VerifyIdentity<'info> {
-    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
-    pub config: Account<'info, Config>,
+pub struct RejectCounterOffer<'info> {
+    #[account(mut, has_one = requester)]
+    pub access_request: Account<'info, AccessRequest>,
+    #[account(
+        mut,
+        seeds = [b"escrow", access_request.key().as_ref()],
+        bump = escrow.bump,
+        close = requester
+    )]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(mut, seeds = [b"escrow_tokens", access_request.key().as_ref()], bump)]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub synid: Account<'info, SynidAccount>,
-    pub authority: Signer<'info>,
+    pub requester: Signer<'info>,
+    pub token_program: Option<Program<'info, Token>>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateReputation<'info> {
-    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
-    pub config: Account<'info, Config>,
-    #[account(mut)]
+pub struct DenyAccess<'info> {
+    #[account(has_one = owner)]
     pub synid: Account<'info, SynidAccount>,
-    pub authority: Signer<'info>,
+    #[account(mut, has_one = synid, has_one = requester)]
+    pub access_request: Account<'info, AccessRequest>,
+    #[account(
+        mut,
+        seeds = [b"escrow", access_request.key().as_ref()],
+        bump = escrow.bump,
+        close = requester
+    )]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(mut, seeds = [b"escrow_tokens", access_request.key().as_ref()], bump)]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub requester: SystemAccount<'info>,
+    pub owner: Signer<'info>,
+    pub token_program: Option<Program<'info, Token>>,
 }
 
 #[derive(Accounts)]
-pub struct BurnSynid<'info> {
-    #[account(mut, has_one = owner, has_one = mint)]
+pub struct ExpireAccessRequest<'info> {
     pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid, has_one = requester, close = requester)]
+    pub access_request: Account<'info, AccessRequest>,
+    #[account(
+        mut,
+        seeds = [b"escrow", access_request.key().as_ref()],
+        bump = escrow.bump,
+        close = requester
+    )]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(mut, seeds = [b"escrow_tokens", access_request.key().as_ref()], bump)]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub token_account: Account<'info, TokenAccount>,
-    #[account(seeds = [b"mint_authority"], bump)]
-    pub mint_authority: SystemAccount<'info>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub requester: SystemAccount<'info>,
+    pub token_program: Option<Program<'info, Token>>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawTreasury<'info> {
-    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
-    pub config: Account<'info, Config>,
-    #[account(mut)]
-    pub treasury: SystemAccount<'info>,
+pub struct CancelAccessRequest<'info> {
+    #[account(mut, has_one = requester, close = requester)]
+    pub access_request: Account<'info, AccessRequest>,
+    #[account(
+        mut,
+        seeds = [b"escrow", access_request.key().as_ref()],
+        bump = escrow.bump,
+        close = requester
+    )]
+    pub escrow: Account<'info, EscrowVault>,
+    #[account(mut, seeds = [b"escrow_tokens", access_request.key().as_ref()], bump)]
+    pub escrow_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub requester: Signer<'info>,
+    pub token_program: Option<Program<'info, Token>>,
 }
 
-#[account]
-pub struct Config {
-    pub authority: Pubkey,
-    pub mint_count: u64,
-    pub mint_price: u64,
+#[derive(Accounts)]
+pub struct ExtendAccessGrant<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid, has_one = requester)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut, seeds = [b"access_policy", synid.key().as_ref()], bump = access_policy.bump)]
+    pub access_policy: Option<Account<'info, AccessPolicy>>,
+    #[account(seeds = [b"requester", requester.key().as_ref()], bump = requester_profile.bump)]
+    pub requester_profile: Option<Account<'info, RequesterProfile>>,
+    #[account(seeds = [b"requester_list", synid.key().as_ref()], bump = requester_list.bump)]
+    pub requester_list: Option<Account<'info, RequesterList>>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + GrantExtension::SIZE,
+        seeds = [b"grant_extension", access_grant.key().as_ref()],
+        bump
+    )]
+    pub grant_extension: Account<'info, GrantExtension>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Option<Account<'info, EarningsVault>>,
+    #[account(mut, address = config.treasury)]
+    pub treasury: Option<SystemAccount<'info>>,
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExtendAccessGrant<'info> {
+    fn try_auto_extend(&mut self, now: i64) -> Result<()> {
+        let (Some(policy), Some(earnings_vault), Some(treasury)) =
+            (self.access_policy.as_mut(), self.earnings_vault.as_mut(), self.treasury.as_ref())
+        else {
+            return Ok(());
+        };
+
+        let grant = &self.access_grant;
+        let extension = &self.grant_extension;
+        let listed = !self.synid.has_requester_list
+            || self
+                .requester_list
+                .as_ref()
+                .is_some_and(|list| list.check(&grant.requester, self.requester_profile.as_deref()).is_ok());
+        if !listed {
+            return Ok(());
+        }
+        let requester_reputation = self.requester_profile.as_ref().map(|p| p.reputation_score);
+        let allowed = policy
+            .check(
+                &grant.requester,
+                &grant.fields,
+                extension.payment,
+                extension.new_expires_at - grant.expires_at,
+                requester_reputation,
+                now,
+            )
+            .is_ok();
+        if !allowed {
+            return Ok(());
+        }
+        policy.record_grant(now);
+
+        let referrer = referrer_for(self.access_grant.referrer, self.referrer.as_mut())?;
+        apply_grant_extension(
+            &mut self.access_grant,
+            &mut self.synid,
+            &self.grant_extension,
+            earnings_vault,
+            &treasury.to_account_info(),
+            referrer,
+            self.config.referral_share_bps,
+        )?;
+        self.grant_extension.close(self.requester.to_account_info())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ApproveGrantExtension<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(
+        mut,
+        seeds = [b"grant_extension", access_grant.key().as_ref()],
+        bump = grant_extension.bump,
+        has_one = access_grant,
+        has_one = requester,
+        close = requester
+    )]
+    pub grant_extension: Account<'info, GrantExtension>,
+    #[account(mut)]
+    pub requester: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut, address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelGrantExtension<'info> {
+    pub synid: Account<'info, SynidAccount>,
+    #[account(has_one = synid)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(
+        mut,
+        seeds = [b"grant_extension", access_grant.key().as_ref()],
+        bump = grant_extension.bump,
+        has_one = access_grant,
+        has_one = requester,
+        close = requester
+    )]
+    pub grant_extension: Account<'info, GrantExtension>,
+    #[account(mut)]
+    pub requester: SystemAccount<'info>,
+    #[account(
+        constraint = authority.key() == synid.owner || authority.key() == requester.key()
+            @ SynidError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAccess<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut, address = access_grant.requester)]
+    pub requester: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut, token::authority = owner)]
+    pub owner_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = requester)]
+    pub requester_tokens: Option<Account<'info, TokenAccount>>,
+    #[account(mut, has_one = synid)]
+    pub mint_earnings: Option<Account<'info, MintEarnings>>,
+    #[account(
+        mut,
+        seeds = [
+            b"earnings_tokens",
+            synid.key().as_ref(),
+            access_grant.payment_mint.unwrap_or_default().as_ref(),
+        ],
+        bump
+    )]
+    pub earnings_tokens: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitEarningsVault<'info> {
+    #[account(has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + EarningsVault::SIZE,
+        seeds = [b"earnings_vault", synid.key().as_ref()],
+        bump
+    )]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimEarnings<'info> {
+    #[account(has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokenEarnings<'info> {
+    #[account(has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(
+        mut,
+        seeds = [b"mint_earnings", synid.key().as_ref(), mint_earnings.mint.as_ref()],
+        bump = mint_earnings.bump,
+        has_one = synid
+    )]
+    pub mint_earnings: Account<'info, MintEarnings>,
+    #[account(
+        mut,
+        seeds = [b"earnings_tokens", synid.key().as_ref(), mint_earnings.mint.as_ref()],
+        bump
+    )]
+    pub earnings_tokens: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint_earnings.mint, token::authority = owner)]
+    pub destination: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAccessRequest<'info> {
+    #[account(mut, has_one = requester, close = requester)]
+    pub access_request: Account<'info, AccessRequest>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAccessGrant<'info> {
+    #[account(has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid, close = rent_payer)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(
+        mut,
+        seeds = [b"key_envelope", access_grant.key().as_ref()],
+        bump = key_envelope.bump,
+        close = owner
+    )]
+    pub key_envelope: Option<Account<'info, KeyEnvelope>>,
+    #[account(mut, address = access_grant.rent_payer)]
+    pub rent_payer: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeliverFieldKeys<'info> {
+    #[account(has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(has_one = synid)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + KeyEnvelope::SIZE,
+        seeds = [b"key_envelope", access_grant.key().as_ref()],
+        bump
+    )]
+    pub key_envelope: Account<'info, KeyEnvelope>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckAccess<'info> {
+    pub synid: Account<'info, SynidAccount>,
+    #[account(has_one = synid)]
+    pub access_grant: Account<'info, AccessGrant>,
+}
+
+#[derive(Accounts)]
+pub struct SettleSubscriptionPeriod<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid, has_one = requester)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut, address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
+    #[account(mut)]
+    pub requester: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordAccessRead<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid, has_one = requester)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut, seeds = [b"earnings_vault", synid.key().as_ref()], bump = earnings_vault.bump)]
+    pub earnings_vault: Account<'info, EarningsVault>,
+    #[account(mut, address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
+    pub requester: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimGrantBudget<'info> {
+    #[account(mut, has_one = requester)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut, has_one = synid, has_one = requester)]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut)]
+    pub requester: SystemAccount<'info>,
+    #[account(
+        constraint = authority.key() == synid.owner || authority.key() == requester.key()
+            @ SynidError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyIdentity<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: Option<SystemAccount<'info>>,
+    #[account(mut, address = synid.mint)]
+    pub mint: Option<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReputation<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: Option<SystemAccount<'info>>,
+    #[account(mut, address = synid.mint)]
+    pub mint: Option<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyRequester<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub requester_profile: Account<'info, RequesterProfile>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSynidMetadata<'info> {
+    #[account(mut, seeds = [b"synid", owner.key().as_ref()], bump = synid.bump, has_one = owner)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: SystemAccount<'info>,
+    #[account(mut, constraint = metadata.mint == synid.mint @ SynidError::MetadataMismatch)]
+    pub metadata: Option<Account<'info, MetadataAccount>>,
+    #[account(mut, address = synid.mint)]
+    pub mint: Option<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub metadata_program: Option<Program<'info, Metadata>>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BurnSynid<'info> {
+    #[account(mut, has_one = owner, has_one = mint)]
+    pub synid: Account<'info, SynidAccount>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(address = synid.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryTokens<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(mut, token::authority = config)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = treasury_vault.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Config {
+    pub authority: Pubkey,
+    pub mint_count: u64,
+    pub mint_price: u64,
     pub access_fee: u64,
     pub treasury: Pubkey,
     pub paused: bool,
     pub total_revenue: u64,
     pub bump: u8,
+    pub require_registered_requester: bool,
+    pub refund_policy: RefundPolicy,
+    pub accepted_mints: Vec<AcceptedMint>,
+    pub platform_fee_bps: u16,
+    pub fee_tiers: Vec<FeeTier>,
+    pub referral_share_bps: u16,
+    pub synid_token_program: Pubkey,
+    pub collection_mint: Pubkey,
+}
+
+impl Config {
+    pub const MAX_ACCEPTED_MINTS: usize = 4;
+    pub const MAX_FEE_TIERS: usize = 8;
+    pub const DEFAULT_PLATFORM_FEE_BPS: u16 = 500;
+    pub const MAX_PLATFORM_FEE_BPS: u16 = 2_000;
+    pub const SIZE: usize = 32 + 8 + 8 + 8 + 32 + 1 + 8 + 1 + 1 + 1
+        + 4 + Self::MAX_ACCEPTED_MINTS * AcceptedMint::SIZE
+        + 2 + 4 + Self::MAX_FEE_TIERS * FeeTier::SIZE
+        + 2 + 32 + 32;
+
+    // Tiers only ever lower the rate, so leaving out the requester profile never helps the payer.
+    pub fn fee_bps(&self, requester_profile: Option<&RequesterProfile>, owner_reputation: u16) -> u16 {
+        self.fee_tiers
+            .iter()
+            .filter(|tier| match tier.condition {
+                FeeTierCondition::RequesterCategory(category) => requester_profile
+                    .is_some_and(|p| p.verified && p.category == category),
+                FeeTierCondition::OwnerReputation { min_score } => owner_reputation >= min_score,
+            })
+            .map(|tier| tier.fee_bps)
+            .fold(self.platform_fee_bps, u16::min)
+    }
+
+    pub fn accepted_mint(&self, mint: &Pubkey) -> Result<&AcceptedMint> {
+        self.accepted_mints
+            .iter()
+            .find(|m| &m.mint == mint)
+            .ok_or_else(|| error!(SynidError::MintNotAccepted))
+    }
+
+    pub fn accepted_mint_mut(&mut self, mint: &Pubkey) -> Result<&mut AcceptedMint> {
+        self.accepted_mints
+            .iter_mut()
+            .find(|m| &m.mint == mint)
+            .ok_or_else(|| error!(SynidError::MintNotAccepted))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct AcceptedMint {
+    pub mint: Pubkey,
+    pub mint_price: u64,
+    pub access_fee: u64,
+    pub total_revenue: u64,
+}
+
+impl AcceptedMint {
+    pub const SIZE: usize = 32 + 8 + 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeTierCondition {
+    RequesterCategory(RequesterCategory),
+    OwnerReputation { min_score: u16 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct FeeTier {
+    pub condition: FeeTierCondition,
+    pub fee_bps: u16,
+}
+
+impl FeeTier {
+    pub const SIZE: usize = 1 + 2 + 2;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RefundPolicy {
+    None,
+    ProRata,
+}
+
+#[account]
+pub struct SynidAccount {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub encrypted_cid: String,
+    pub encryption_key_hash: [u8; 32],
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub token_id: u64,
+    pub soulbound: bool,
+    pub access_count: u64,
+    pub total_earnings: u64,
+    pub reputation_score: u16,
+    pub verified: bool,
+    pub bump: u8,
+    pub token_program: Pubkey,
+    pub has_field_pricing: bool,
+    pub has_requester_list: bool,
+}
+
+impl SynidAccount {
+    pub const SIZE: usize = 32 + 32 + 132 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 2 + 1 + 1 + 32 + 1 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct FieldPrice {
+    pub field: String,
+    pub price: u64,
+    pub available: bool,
+}
+
+#[account]
+pub struct FieldPricing {
+    pub synid: Pubkey,
+    pub min_payment: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub prices: Vec<FieldPrice>,
+    pub bump: u8,
+}
+
+impl FieldPricing {
+    pub const MAX_FIELDS: usize = 16;
+    pub const MAX_FIELD_LEN: usize = 32;
+    pub const SIZE: usize = 32 + 8 + 33 + 4 + Self::MAX_FIELDS * (4 + Self::MAX_FIELD_LEN + 8 + 1) + 1;
+
+    pub fn quote(&self, fields: &[String]) -> Result<u64> {
+        Ok(self.price_of(fields)?.max(self.min_payment))
+    }
+
+    pub fn price_of(&self, fields: &[String]) -> Result<u64> {
+        let mut total: u64 = 0;
+        for field in fields {
+            let entry = self
+                .prices
+                .iter()
+                .find(|p| &p.field == field)
+                .ok_or(SynidError::FieldNotPriced)?;
+            require!(entry.available, SynidError::FieldUnavailable);
+            total = total.checked_add(entry.price).ok_or(SynidError::MathOverflow)?;
+        }
+        Ok(total)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct PolicyField {
+    pub field: String,
+    pub min_price: u64,
+}
+
+#[account]
+pub struct AccessPolicy {
+    pub synid: Pubkey,
+    pub enabled: bool,
+    pub fields: Vec<PolicyField>,
+    pub max_duration: i64,
+    pub allowed_requesters: Vec<Pubkey>,
+    pub min_requester_reputation: u16,
+    pub daily_cap: u32,
+    pub window_start: i64,
+    pub window_grants: u32,
+    pub bump: u8,
+}
+
+impl AccessPolicy {
+    pub const MAX_FIELDS: usize = 16;
+    pub const MAX_REQUESTERS: usize = 16;
+    pub const SIZE: usize = 32
+        + 1
+        + 4
+        + Self::MAX_FIELDS * (4 + FieldPricing::MAX_FIELD_LEN + 8)
+        + 8
+        + 4
+        + Self::MAX_REQUESTERS * 32
+        + 2
+        + 4
+        + 8
+        + 4
+        + 1;
+    pub const WINDOW: i64 = 86_400;
+
+    pub fn authorize(
+        &mut self,
+        requester: &Pubkey,
+        fields: &[String],
+        payment: u64,
+        duration: i64,
+        requester_reputation: Option<u16>,
+        now: i64,
+    ) -> Result<()> {
+        self.check(requester, fields, payment, duration, requester_reputation, now)?;
+        self.record_grant(now);
+        Ok(())
+    }
+
+    pub fn check(
+        &self,
+        requester: &Pubkey,
+        fields: &[String],
+        payment: u64,
+        duration: i64,
+        requester_reputation: Option<u16>,
+        now: i64,
+    ) -> Result<()> {
+        require!(self.enabled, SynidError::PolicyNotSatisfied);
+        require!(duration > 0 && duration <= self.max_duration, SynidError::PolicyNotSatisfied);
+        require!(
+            self.allowed_requesters.is_empty() || self.allowed_requesters.contains(requester),
+            SynidError::PolicyNotSatisfied
+        );
+        if self.min_requester_reputation > 0 {
+            require!(
+                requester_reputation.unwrap_or(0) >= self.min_requester_reputation,
+                SynidError::PolicyNotSatisfied
+            );
+        }
+
+        let mut min_total: u64 = 0;
+        for field in fields {
+            let entry = self
+                .fields
+                .iter()
+                .find(|f| &f.field == field)
+                .ok_or(SynidError::PolicyNotSatisfied)?;
+            min_total = min_total.checked_add(entry.min_price).ok_or(SynidError::MathOverflow)?;
+        }
+        require!(payment >= min_total, SynidError::PolicyNotSatisfied);
+
+        if self.daily_cap > 0 {
+            let window_grants = if now - self.window_start >= Self::WINDOW {
+                0
+            } else {
+                self.window_grants
+            };
+            require!(window_grants < self.daily_cap, SynidError::DailyCapReached);
+        }
+
+        Ok(())
+    }
+
+    pub fn record_grant(&mut self, now: i64) {
+        if now - self.window_start >= Self::WINDOW {
+            self.window_start = now - now.rem_euclid(Self::WINDOW);
+            self.window_grants = 0;
+        }
+        self.window_grants += 1;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RequesterCategory {
+    Game,
+    Defi,
+    Social,
+    Marketplace,
+    Identity,
+    Other,
+}
+
+#[account]
+pub struct RequesterProfile {
+    pub authority: Pubkey,
+    pub display_name: String,
+    pub domain: String,
+    pub logo_uri: String,
+    pub encryption_pubkey: [u8; 32],
+    pub category: RequesterCategory,
+    pub verified: bool,
+    pub reputation_score: u16,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl RequesterProfile {
+    pub const SIZE: usize = 32 + 36 + 68 + 204 + 32 + 1 + 1 + 2 + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RequesterListKind {
+    Blocked,
+    Allowed,
+}
+
+#[account]
+pub struct RequesterList {
+    pub synid: Pubkey,
+    pub allowlist_only: bool,
+    pub blocked: Vec<Pubkey>,
+    pub allowed: Vec<Pubkey>,
+    pub allowed_categories: Vec<RequesterCategory>,
+    pub bump: u8,
+}
+
+impl RequesterList {
+    pub const MAX_ENTRIES: usize = 64;
+    pub const MAX_CATEGORIES: usize = 8;
+    pub const SIZE: usize = 32
+        + 1
+        + 4
+        + Self::MAX_ENTRIES * 32
+        + 4
+        + Self::MAX_ENTRIES * 32
+        + 4
+        + Self::MAX_CATEGORIES
+        + 1;
+
+    pub fn entries_mut(&mut self, kind: RequesterListKind) -> &mut Vec<Pubkey> {
+        match kind {
+            RequesterListKind::Blocked => &mut self.blocked,
+            RequesterListKind::Allowed => &mut self.allowed,
+        }
+    }
+
+    pub fn check(&self, requester: &Pubkey, profile: Option<&RequesterProfile>) -> Result<()> {
+        require!(!self.blocked.contains(requester), SynidError::RequesterBlocked);
+        if self.allowlist_only {
+            let category_allowed = profile
+                .is_some_and(|p| p.verified && self.allowed_categories.contains(&p.category));
+            require!(
+                self.allowed.contains(requester) || category_allowed,
+                SynidError::RequesterNotAllowed
+            );
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct AccessRelationship {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub next_request_id: u64,
+    pub bump: u8,
+}
+
+impl AccessRelationship {
+    pub const SIZE: usize = 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct AccessRequest {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub request_id: u64,
+    pub fields: Vec<String>,
+    pub offered_payment: u64,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub encryption_pubkey: [u8; 32],
+    pub terms: AccessTerms,
+    pub payment_mint: Option<Pubkey>,
+    pub referrer: Option<Pubkey>,
+    pub status: AccessStatus,
+    pub counter_offer: Option<CounterOffer>,
+    pub bump: u8,
+}
+
+impl AccessRequest {
+    pub const MAX_FIELDS: usize = 10;
+    pub const FIELDS_SIZE: usize = 4 + Self::MAX_FIELDS * (4 + FieldPricing::MAX_FIELD_LEN);
+    pub const SIZE: usize = 32
+        + 32
+        + 8
+        + Self::FIELDS_SIZE
+        + 8
+        + 8
+        + 8
+        + 32
+        + AccessTerms::SIZE
+        + 33
+        + 33
+        + 2
+        + CounterOffer::SIZE
+        + 1;
+
+    pub fn is_open(&self) -> bool {
+        matches!(self.status, AccessStatus::Pending | AccessStatus::Countered)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct CounterOffer {
+    pub payment: u64,
+    pub expires_at: i64,
+    pub fields: Vec<String>,
+    pub proposed_at: i64,
+}
+
+impl CounterOffer {
+    pub const SIZE: usize = 1 + 8 + 8 + AccessRequest::FIELDS_SIZE + 8;
+}
+
+#[account]
+pub struct AccessGrant {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub request_id: u64,
+    pub encryption_pubkey: [u8; 32],
+    pub requested_fields: Vec<String>,
+    pub fields: Vec<String>,
+    pub terms: AccessTerms,
+    pub payment_mint: Option<Pubkey>,
+    pub fee_bps: u16,
+    pub referrer: Option<Pubkey>,
+    pub price: u64,
+    pub payment: u64,
+    pub owner_payment: u64,
+    pub budget: u64,
+    pub read_count: u64,
+    pub granted_at: i64,
+    pub term_start: i64,
+    pub expires_at: i64,
+    pub active: bool,
+    pub bump: u8,
+    pub rent_payer: Pubkey,
+}
+
+impl AccessGrant {
+    pub const SIZE: usize = 32
+        + 32
+        + 8
+        + 32
+        + AccessRequest::FIELDS_SIZE
+        + AccessRequest::FIELDS_SIZE
+        + AccessTerms::SIZE
+        + 33
+        + 2
+        + 33
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 1
+        + 32;
+
+    pub fn unused_payment(&self, now: i64) -> u64 {
+        let time_based = !matches!(self.terms, AccessTerms::Metered { .. });
+        if !time_based || now >= self.expires_at || self.term_start >= self.expires_at {
+            return 0;
+        }
+        let remaining = self.expires_at - now.max(self.term_start);
+        pro_rata(self.owner_payment, remaining as u64, (self.expires_at - self.term_start) as u64)
+    }
+
+    pub fn access_status(&self, requester: &Pubkey, fields: &[String], now: i64) -> AccessCheckStatus {
+        // A subscription past its boundary with no budget left has lapsed, even before the crank runs.
+        let budget_exhausted = match self.terms {
+            AccessTerms::OneTime => false,
+            AccessTerms::Subscription { .. } => now >= self.expires_at && self.budget < self.price,
+            AccessTerms::Metered { .. } => self.budget < self.price,
+        };
+
+        if &self.requester != requester {
+            AccessCheckStatus::NotGranted
+        } else if !self.active {
+            AccessCheckStatus::Revoked
+        } else if budget_exhausted {
+            AccessCheckStatus::BudgetExhausted
+        } else if now >= self.expires_at {
+            AccessCheckStatus::Expired
+        } else if !fields.iter().all(|field| self.fields.contains(field)) {
+            AccessCheckStatus::MissingFields
+        } else {
+            AccessCheckStatus::Granted
+        }
+    }
+}
+
+#[account]
+pub struct Referrer {
+    pub authority: Pubkey,
+    pub total_earned: u64,
+    pub total_claimed: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub const SIZE: usize = 32 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct MintEarnings {
+    pub synid: Pubkey,
+    pub mint: Pubkey,
+    pub total_earnings: u64,
+    pub total_credited: u64,
+    pub total_claimed: u64,
+    pub total_refunded: u64,
+    pub bump: u8,
+}
+
+impl MintEarnings {
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct EarningsVault {
+    pub synid: Pubkey,
+    pub total_credited: u64,
+    pub total_claimed: u64,
+    pub total_refunded: u64,
+    pub bump: u8,
+}
+
+impl EarningsVault {
+    pub const SIZE: usize = 32 + 8 + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTerms {
+    OneTime,
+    Subscription { period: i64, periods: u32 },
+    Metered { reads: u32 },
+}
+
+impl AccessTerms {
+    pub const SIZE: usize = 1 + 8 + 4;
+
+    pub fn deposit(&self, price: u64) -> Result<u64> {
+        match *self {
+            AccessTerms::OneTime => Ok(price),
+            AccessTerms::Subscription { period, periods } => {
+                require!(period > 0 && periods > 0, SynidError::UnsupportedTerms);
+                price
+                    .checked_mul(periods as u64)
+                    .ok_or_else(|| error!(SynidError::MathOverflow))
+            }
+            AccessTerms::Metered { reads } => {
+                require!(reads > 0, SynidError::UnsupportedTerms);
+                price
+                    .checked_mul(reads as u64)
+                    .ok_or_else(|| error!(SynidError::MathOverflow))
+            }
+        }
+    }
+}
+
+#[account]
+pub struct GrantExtension {
+    pub access_grant: Pubkey,
+    pub requester: Pubkey,
+    pub new_expires_at: i64,
+    pub payment: u64,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+impl GrantExtension {
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct WrappedFieldKey {
+    pub field: String,
+    pub ciphertext: [u8; 48],
+}
+
+impl WrappedFieldKey {
+    pub const CIPHERTEXT_LEN: usize = 48;
+    pub const SIZE: usize = 4 + FieldPricing::MAX_FIELD_LEN + Self::CIPHERTEXT_LEN;
+}
+
+#[account]
+pub struct KeyEnvelope {
+    pub access_grant: Pubkey,
+    pub requester: Pubkey,
+    pub recipient_pubkey: [u8; 32],
+    pub ephemeral_pubkey: [u8; 32],
+    pub keys: Vec<WrappedFieldKey>,
+    pub delivered_at: i64,
+    pub bump: u8,
+}
+
+impl KeyEnvelope {
+    pub const MAX_KEYS: usize = 10;
+    pub const SIZE: usize = 32 + 32 + 32 + 32 + 4 + Self::MAX_KEYS * WrappedFieldKey::SIZE + 8 + 1;
+}
+
+#[account]
+pub struct EscrowVault {
+    pub access_request: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl EscrowVault {
+    pub const SIZE: usize = 32 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessCheckStatus {
+    Granted,
+    NotGranted,
+    Revoked,
+    Expired,
+    MissingFields,
+    BudgetExhausted,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessCheck {
+    pub status: AccessCheckStatus,
+    pub granted_at: i64,
+    pub expires_at: i64,
+}
+
+impl AccessCheck {
+    pub fn is_granted(&self) -> bool {
+        self.status == AccessCheckStatus::Granted
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum AccessStatus {
+    Pending,
+    Approved,
+    Denied,
+    Expired,
+    Countered,
+    Rejected,
+}
+
+#[event]
+pub struct SynidMinted {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProfileUpdated {
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarningsClaimed {
+    pub synid: Pubkey,
+    pub owner: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub total_credited: u64,
+    pub total_claimed: u64,
+    pub available: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralEarned {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_earned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralEarningsClaimed {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_earned: u64,
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeTiersUpdated {
+    pub platform_fee_bps: u16,
+    pub tiers: Vec<FeeTier>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AcceptedMintUpdated {
+    pub mint: Pubkey,
+    pub treasury_vault: Pubkey,
+    pub mint_price: u64,
+    pub access_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollectionCreated {
+    pub collection_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AcceptedMintRemoved {
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FieldPricingUpdated {
+    pub synid: Pubkey,
+    pub owner: Pubkey,
+    pub min_payment: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccessPolicyUpdated {
+    pub synid: Pubkey,
+    pub owner: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RequesterListUpdated {
+    pub synid: Pubkey,
+    pub allowlist_only: bool,
+    pub blocked_count: u32,
+    pub allowed_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RequesterRegistered {
+    pub authority: Pubkey,
+    pub category: RequesterCategory,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RequesterProfileUpdated {
+    pub authority: Pubkey,
+    pub verified: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RequesterVerified {
+    pub authority: Pubkey,
+    pub verified: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RequesterReputationUpdated {
+    pub authority: Pubkey,
+    pub new_score: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccessRequested {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub request_id: u64,
+    pub fields: Vec<String>,
+    pub payment: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub referrer: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccessApproved {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub requested_fields: Vec<String>,
+    pub granted_fields: Vec<String>,
+    pub payment: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub platform_fee: u64,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccessCountered {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub request_id: u64,
+    pub payment: u64,
+    pub expires_at: i64,
+    pub fields: Vec<String>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CounterOfferAccepted {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub request_id: u64,
+    pub payment: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub topped_up: u64,
+    pub refunded: u64,
+    pub timestamp: i64,
 }
 
-impl Config {
-    pub const SIZE: usize = 32 + 8 + 8 + 8 + 32 + 1 + 8 + 1;
+#[event]
+pub struct CounterOfferRejected {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub request_id: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub refunded: u64,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct SynidAccount {
-    pub owner: Pubkey,
-    pub mint: Pubkey,
-    pub encrypted_cid: String,
-    pub encryption_key_hash: [u8; 32],
-    pub created_at: i64,
-    pub updated_at: i64,
-    pub token_id: u64,
-    pub soulbound: bool,
-    pub access_count: u64,
-    pub total_earnings: u64,
-    pub reputation_score: u16,
-    pub verified: bool,
-    pub bump: u8,
+#[event]
+pub struct AccessDenied {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub timestamp: i64,
 }
 
-impl SynidAccount {
-    pub const SIZE: usize = 32 + 32 + 132 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 2 + 1 + 1;
+#[event]
+pub struct AccessExpired {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub refunded: u64,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct AccessRequest {
+#[event]
+pub struct AccessRequestCancelled {
     pub synid: Pubkey,
     pub requester: Pubkey,
-    pub fields: Vec<String>,
-    pub offered_payment: u64,
-    pub created_at: i64,
-    pub expires_at: i64,
-    pub status: AccessStatus,
-    pub bump: u8,
+    pub payment_mint: Option<Pubkey>,
+    pub refunded: u64,
+    pub timestamp: i64,
 }
 
-impl AccessRequest {
-    pub const SIZE: usize = 32 + 32 + 260 + 8 + 8 + 8 + 2 + 1;
+#[event]
+pub struct FieldKeysDelivered {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub access_grant: Pubkey,
+    pub field_count: u32,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct AccessGrant {
+#[event]
+pub struct GrantExtensionRequested {
     pub synid: Pubkey,
     pub requester: Pubkey,
-    pub fields: Vec<String>,
+    pub access_grant: Pubkey,
+    pub new_expires_at: i64,
     pub payment: u64,
-    pub granted_at: i64,
-    pub expires_at: i64,
-    pub active: bool,
-    pub bump: u8,
+    pub timestamp: i64,
 }
 
-impl AccessGrant {
-    pub const SIZE: usize = 32 + 32 + 260 + 8 + 8 + 8 + 1 + 1;
+#[event]
+pub struct GrantExtended {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub access_grant: Pubkey,
+    pub previous_expires_at: i64,
+    pub new_expires_at: i64,
+    pub payment: u64,
+    pub platform_fee: u64,
+    pub timestamp: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum AccessStatus {
-    Pending,
-    Approved,
-    Denied,
-    Expired,
+#[event]
+pub struct GrantExtensionCancelled {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub access_grant: Pubkey,
+    pub refunded: u64,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct SynidMinted {
-    pub owner: Pubkey,
-    pub mint: Pubkey,
-    pub token_id: u64,
+pub struct SubscriptionSettled {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub access_grant: Pubkey,
+    pub payment: u64,
+    pub platform_fee: u64,
+    pub remaining_budget: u64,
+    pub period_ends_at: i64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct ProfileUpdated {
-    pub owner: Pubkey,
+pub struct SubscriptionLapsed {
+    pub synid: Pubkey,
+    pub requester: Pubkey,
+    pub access_grant: Pubkey,
+    pub refunded: u64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct AccessRequested {
+pub struct AccessRead {
     pub synid: Pubkey,
     pub requester: Pubkey,
-    pub fields: Vec<String>,
-    pub payment: u64,
+    pub access_grant: Pubkey,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub data_version: Option<[u8; 32]>,
+    pub remaining_budget: u64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct AccessApproved {
+pub struct GrantBudgetReclaimed {
     pub synid: Pubkey,
     pub requester: Pubkey,
-    pub payment: u64,
+    pub access_grant: Pubkey,
+    pub refunded: u64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct AccessDenied {
+pub struct SubscriptionCancelled {
     pub synid: Pubkey,
     pub requester: Pubkey,
+    pub access_grant: Pubkey,
+    pub refunded: u64,
+    pub cancelled_by: Pubkey,
     pub timestamp: i64,
 }
 
@@ -648,12 +4202,15 @@ pub struct AccessDenied {
 pub struct AccessRevoked {
     pub synid: Pubkey,
     pub requester: Pubkey,
-    pub timestamp: i64,
+    pub payment_mint: Option<Pubkey>,
+    pub refunded: u64,
+    pub revoked_at: i64,
 }
 
 #[event]
 pub struct IdentityVerified {
     pub owner: Pubkey,
+    pub attributes_uri: Option<String>,
     pub timestamp: i64,
 }
 
@@ -661,6 +4218,16 @@ pub struct IdentityVerified {
 pub struct ReputationUpdated {
     pub owner: Pubkey,
     pub new_score: u16,
+    pub attributes_uri: Option<String>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SynidMetadataUpdated {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub uri: String,
     pub timestamp: i64,
 }
 
@@ -697,4 +4264,826 @@ pub enum SynidError {
     AlreadyRevoked,
     #[msg("Program paused")]
     Paused,
+    #[msg("Request not expired")]
+    RequestNotExpired,
+    #[msg("Grant still active")]
+    GrantStillActive,
+    #[msg("Field name too long")]
+    FieldNameTooLong,
+    #[msg("Duplicate field")]
+    DuplicateField,
+    #[msg("Field not priced")]
+    FieldNotPriced,
+    #[msg("Field unavailable")]
+    FieldUnavailable,
+    #[msg("Field pricing account required")]
+    FieldPricingRequired,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Invalid field selection")]
+    InvalidFieldSelection,
+    #[msg("Too many requesters")]
+    TooManyRequesters,
+    #[msg("Access policy not satisfied")]
+    PolicyNotSatisfied,
+    #[msg("Daily cap reached")]
+    DailyCapReached,
+    #[msg("Settlement account missing")]
+    SettlementAccountMissing,
+    #[msg("Requester blocked")]
+    RequesterBlocked,
+    #[msg("Requester not on allowlist")]
+    RequesterNotAllowed,
+    #[msg("Requester list account required")]
+    RequesterListRequired,
+    #[msg("Too many categories")]
+    TooManyCategories,
+    #[msg("Domain too long")]
+    DomainTooLong,
+    #[msg("Requester not registered")]
+    RequesterNotRegistered,
+    #[msg("Grant inactive")]
+    GrantInactive,
+    #[msg("Invalid expiry")]
+    InvalidExpiry,
+    #[msg("Unsupported access terms")]
+    UnsupportedTerms,
+    #[msg("Subscription period not elapsed")]
+    PeriodNotElapsed,
+    #[msg("Grant budget remaining")]
+    BudgetRemaining,
+    #[msg("Grant budget exhausted")]
+    BudgetExhausted,
+    #[msg("Payment mint not accepted")]
+    MintNotAccepted,
+    #[msg("Too many accepted mints")]
+    TooManyMints,
+    #[msg("Payment mint mismatch")]
+    PaymentMintMismatch,
+    #[msg("Token account missing")]
+    TokenAccountMissing,
+    #[msg("Platform fee too high")]
+    FeeTooHigh,
+    #[msg("Too many fee tiers")]
+    TooManyFeeTiers,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("Unsupported token program")]
+    UnsupportedTokenProgram,
+    #[msg("Collection already created")]
+    CollectionAlreadyCreated,
+    #[msg("Collection accounts missing")]
+    CollectionAccountsMissing,
+    #[msg("Metadata accounts missing")]
+    MetadataAccountsMissing,
+    #[msg("Metadata does not belong to this SynID")]
+    MetadataMismatch,
+    #[msg("Insufficient earnings")]
+    InsufficientEarnings,
+    #[msg("Account already uses the current layout")]
+    AlreadyMigrated,
+    #[msg("Invalid account layout")]
+    InvalidAccountLayout,
+    #[msg("Owner cannot cover the refund")]
+    InsufficientRefundFunds,
+    #[msg("Referrals are only supported for SOL payments")]
+    ReferralUnsupportedForMint,
+}
+
+fn settle_payment<'info>(
+    source: &AccountInfo<'info>,
+    earnings_vault: &mut Account<'info, EarningsVault>,
+    treasury: &AccountInfo<'info>,
+    referrer: Option<&mut Account<'info, Referrer>>,
+    payment: u64,
+    fee_bps: u16,
+    referral_share_bps: u16,
+) -> Result<(u64, u64)> {
+    let referral_share_bps = if referrer.is_some() { referral_share_bps } else { 0 };
+    let (owner_payment, platform_fee, referral_fee) = split_payment(payment, fee_bps, referral_share_bps);
+
+    **source.try_borrow_mut_lamports()? -= payment;
+    **earnings_vault.to_account_info().try_borrow_mut_lamports()? += owner_payment;
+    earnings_vault.total_credited += owner_payment;
+    **treasury.try_borrow_mut_lamports()? += platform_fee - referral_fee;
+
+    if let Some(referrer) = referrer {
+        if referral_fee > 0 {
+            **referrer.to_account_info().try_borrow_mut_lamports()? += referral_fee;
+            referrer.total_earned += referral_fee;
+
+            emit!(ReferralEarned {
+                referrer: referrer.key(),
+                authority: referrer.authority,
+                amount: referral_fee,
+                total_earned: referrer.total_earned,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+    }
+
+    Ok((owner_payment, platform_fee))
+}
+
+fn referrer_for<'a, 'info>(
+    expected: Option<Pubkey>,
+    referrer: Option<&'a mut Account<'info, Referrer>>,
+) -> Result<Option<&'a mut Account<'info, Referrer>>> {
+    match expected {
+        Some(key) => {
+            let referrer = referrer.ok_or(SynidError::InvalidReferrer)?;
+            require_keys_eq!(referrer.key(), key, SynidError::InvalidReferrer);
+            Ok(Some(referrer))
+        }
+        None => Ok(None),
+    }
+}
+
+fn transfer_escrow_tokens<'info>(
+    escrow: &Account<'info, EscrowVault>,
+    escrow_tokens: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"escrow".as_ref(), escrow.access_request.as_ref(), &[escrow.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: escrow_tokens.to_account_info(),
+                to: destination.to_account_info(),
+                authority: escrow.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+fn close_escrow_tokens<'info>(
+    escrow: &Account<'info, EscrowVault>,
+    escrow_tokens: &Account<'info, TokenAccount>,
+    rent_recipient: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let seeds = &[b"escrow".as_ref(), escrow.access_request.as_ref(), &[escrow.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow_tokens.to_account_info(),
+            destination: rent_recipient.clone(),
+            authority: escrow.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+fn refund_escrow_tokens<'info>(
+    mint: Pubkey,
+    escrow: &Account<'info, EscrowVault>,
+    escrow_tokens: Option<&Account<'info, TokenAccount>>,
+    requester_tokens: Option<&Account<'info, TokenAccount>>,
+    requester: &AccountInfo<'info>,
+    token_program: Option<&Program<'info, Token>>,
+) -> Result<()> {
+    let escrow_tokens = escrow_tokens.ok_or(SynidError::TokenAccountMissing)?;
+    let requester_tokens = requester_tokens.ok_or(SynidError::TokenAccountMissing)?;
+    let token_program = token_program.ok_or(SynidError::TokenAccountMissing)?;
+    require_keys_eq!(requester_tokens.mint, mint, SynidError::PaymentMintMismatch);
+
+    if escrow_tokens.amount > 0 {
+        transfer_escrow_tokens(escrow, escrow_tokens, requester_tokens, token_program, escrow_tokens.amount)?;
+    }
+    close_escrow_tokens(escrow, escrow_tokens, requester, token_program)
+}
+
+fn apply_grant_extension<'info>(
+    grant: &mut Account<'info, AccessGrant>,
+    synid: &mut Account<'info, SynidAccount>,
+    extension: &Account<'info, GrantExtension>,
+    earnings_vault: &mut Account<'info, EarningsVault>,
+    treasury: &AccountInfo<'info>,
+    referrer: Option<&mut Account<'info, Referrer>>,
+    referral_share_bps: u16,
+) -> Result<()> {
+    let (owner_payment, platform_fee) = settle_payment(
+        &extension.to_account_info(),
+        earnings_vault,
+        treasury,
+        referrer,
+        extension.payment,
+        grant.fee_bps,
+        referral_share_bps,
+    )?;
+
+    let previous_expires_at = grant.expires_at;
+    grant.expires_at = extension.new_expires_at;
+    grant.payment += extension.payment;
+    grant.owner_payment += owner_payment;
+    synid.total_earnings += extension.payment;
+
+    emit!(GrantExtended {
+        synid: grant.synid,
+        requester: grant.requester,
+        access_grant: grant.key(),
+        previous_expires_at,
+        new_expires_at: grant.expires_at,
+        payment: extension.payment,
+        platform_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn collect_mint_price<'info>(
+    config: &mut Account<'info, Config>,
+    owner: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    payment_mint: Option<&Account<'info, Mint>>,
+    payer_tokens: Option<&Account<'info, TokenAccount>>,
+    treasury_tokens: Option<&Account<'info, TokenAccount>>,
+    token_program: Option<AccountInfo<'info>>,
+) -> Result<()> {
+    if let Some(payment_mint) = payment_mint {
+        let accepted = config.accepted_mint_mut(&payment_mint.key())?;
+        if accepted.mint_price > 0 {
+            let payer_tokens = payer_tokens.ok_or(SynidError::TokenAccountMissing)?;
+            let treasury_tokens = treasury_tokens.ok_or(SynidError::TokenAccountMissing)?;
+            let token_program = token_program.ok_or(SynidError::TokenAccountMissing)?;
+            require_keys_eq!(treasury_tokens.mint, accepted.mint, SynidError::PaymentMintMismatch);
+            token::transfer(
+                CpiContext::new(
+                    token_program,
+                    Transfer {
+                        from: payer_tokens.to_account_info(),
+                        to: treasury_tokens.to_account_info(),
+                        authority: owner.clone(),
+                    },
+                ),
+                accepted.mint_price,
+            )?;
+            accepted.total_revenue += accepted.mint_price;
+        }
+    } else if config.mint_price > 0 {
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            owner.key,
+            &config.treasury,
+            config.mint_price,
+        );
+        anchor_lang::solana_program::program::invoke(&transfer_ix, &[owner.clone(), treasury.clone()])?;
+        config.total_revenue += config.mint_price;
+    }
+    Ok(())
+}
+
+fn update_metaplex_metadata<'info>(
+    metadata_program: AccountInfo<'info>,
+    metadata: &Account<'info, MetadataAccount>,
+    mint_authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    name: Option<String>,
+    uri: String,
+) -> Result<()> {
+    // Metaplex stores strings null-padded, so carry the unchanged fields over trimmed.
+    let data = DataV2 {
+        name: name.unwrap_or_else(|| metadata.name.trim_end_matches('\0').to_string()),
+        symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+        uri,
+        seller_fee_basis_points: metadata.seller_fee_basis_points,
+        creators: metadata.creators.clone(),
+        collection: metadata.collection.clone(),
+        uses: metadata.uses.clone(),
+    };
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            metadata_program,
+            UpdateMetadataAccountsV2 {
+                metadata: metadata.to_account_info(),
+                update_authority: mint_authority,
+            },
+            signer_seeds,
+        ),
+        None,
+        Some(data),
+        None,
+        None,
+    )
+}
+
+fn update_token_2022_metadata<'info>(
+    token_2022_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    mint_authority: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    fields: Vec<(Field, String)>,
+) -> Result<()> {
+    // The token program reallocs the mint on update but does not fund the extra rent.
+    let required_len = {
+        let data = mint.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        let mut metadata = state.get_variable_len_extension::<TokenMetadata>()?;
+        let current_size = metadata.tlv_size_of()?;
+        for (field, value) in &fields {
+            metadata.update(field.clone(), value.clone());
+        }
+        data.len() - current_size + metadata.tlv_size_of()?
+    };
+    let required_lamports = Rent::get()?.minimum_balance(required_len);
+    if required_lamports > mint.lamports() {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: mint.clone(),
+                },
+            ),
+            required_lamports - mint.lamports(),
+        )?;
+    }
+
+    for (field, value) in fields {
+        anchor_lang::solana_program::program::invoke_signed(
+            &spl_token_metadata_interface::instruction::update_field(
+                token_2022_program.key,
+                mint.key,
+                mint_authority.key,
+                field,
+                value,
+            ),
+            &[mint.clone(), mint_authority.clone()],
+            signer_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+// The metadata URI belongs to the owner (`update_synid_metadata`), so the authority's
+// badge attributes go in a separate Token-2022 metadata field. Metaplex metadata has no
+// room for a second pointer, so only Token-2022 SynIDs can carry one.
+fn set_attributes_uri<'info>(
+    mint: Option<&InterfaceAccount<'info, token_interface::Mint>>,
+    mint_authority: Option<&SystemAccount<'info>>,
+    mint_authority_bump: u8,
+    token_2022_program: Option<&Program<'info, Token2022>>,
+    payer: AccountInfo<'info>,
+    system_program: Option<&Program<'info, System>>,
+    uri: String,
+) -> Result<()> {
+    require!(uri.len() <= 200, SynidError::UriTooLong);
+    let (Some(mint), Some(mint_authority), Some(token_2022_program), Some(system_program)) =
+        (mint, mint_authority, token_2022_program, system_program)
+    else {
+        return err!(SynidError::MetadataAccountsMissing);
+    };
+    require_keys_eq!(*mint.to_account_info().owner, token_2022::ID, SynidError::UnsupportedTokenProgram);
+
+    let seeds = &[b"mint_authority".as_ref(), &[mint_authority_bump]];
+    update_token_2022_metadata(
+        token_2022_program.to_account_info(),
+        mint.to_account_info(),
+        mint_authority.to_account_info(),
+        payer,
+        system_program.to_account_info(),
+        &[&seeds[..]],
+        vec![(Field::Key("attributes".to_string()), uri)],
+    )
+}
+
+// Accounts created before a layout change are shorter than the current `SIZE`; new
+// fields are only ever appended, so the zero-filled tail decodes as their defaults.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    require!(account.data_len() < new_len, SynidError::AlreadyMigrated);
+
+    let required_lamports = Rent::get()?.minimum_balance(new_len);
+    if required_lamports > account.lamports() {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: account.clone(),
+                },
+            ),
+            required_lamports - account.lamports(),
+        )?;
+    }
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+fn withdrawable_lamports(account: &AccountInfo) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(account.data_len());
+    Ok(account.lamports().saturating_sub(rent_exempt))
+}
+
+// Weights are field prices; without any priced weight the split falls back to field counts.
+fn partial_payment(escrowed: u64, granted: (u64, usize), requested: (u64, usize)) -> u64 {
+    if requested.0 == 0 {
+        pro_rata(escrowed, granted.1 as u64, requested.1 as u64)
+    } else {
+        pro_rata(escrowed, granted.0, requested.0)
+    }
+}
+
+// Returns (owner payment, platform fee, referrer's cut of the platform fee).
+fn split_payment(payment: u64, fee_bps: u16, referral_share_bps: u16) -> (u64, u64, u64) {
+    let platform_fee = platform_fee(payment, fee_bps);
+    let referral_fee = pro_rata(platform_fee, referral_share_bps as u64, 10_000);
+    (payment - platform_fee, platform_fee, referral_fee)
+}
+
+fn platform_fee(payment: u64, fee_bps: u16) -> u64 {
+    pro_rata(payment, fee_bps as u64, 10_000)
+}
+
+fn pro_rata(amount: u64, part: u64, whole: u64) -> u64 {
+    (amount as u128 * part as u128 / whole as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant(terms: AccessTerms, price: u64, budget: u64) -> AccessGrant {
+        AccessGrant {
+            synid: Pubkey::new_unique(),
+            requester: Pubkey::new_unique(),
+            request_id: 0,
+            encryption_pubkey: [0u8; 32],
+            requested_fields: vec!["email".to_string(), "country".to_string()],
+            fields: vec!["email".to_string(), "country".to_string()],
+            terms,
+            payment_mint: None,
+            fee_bps: Config::DEFAULT_PLATFORM_FEE_BPS,
+            referrer: None,
+            price,
+            payment: price,
+            owner_payment: price,
+            budget,
+            read_count: 0,
+            granted_at: 100,
+            term_start: 100,
+            expires_at: 200,
+            active: true,
+            bump: 255,
+            rent_payer: Pubkey::new_unique(),
+        }
+    }
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn access_status_granted() {
+        let g = grant(AccessTerms::OneTime, 1_000, 0);
+        let status = g.access_status(&g.requester, &fields(&["email"]), 150);
+        assert_eq!(status, AccessCheckStatus::Granted);
+        assert!(AccessCheck { status, granted_at: 100, expires_at: 200 }.is_granted());
+    }
+
+    #[test]
+    fn access_status_not_granted_for_other_requester() {
+        let g = grant(AccessTerms::OneTime, 1_000, 0);
+        assert_eq!(
+            g.access_status(&Pubkey::new_unique(), &fields(&["email"]), 150),
+            AccessCheckStatus::NotGranted
+        );
+    }
+
+    #[test]
+    fn access_status_revoked() {
+        let mut g = grant(AccessTerms::OneTime, 1_000, 0);
+        g.active = false;
+        assert_eq!(g.access_status(&g.requester, &fields(&["email"]), 150), AccessCheckStatus::Revoked);
+    }
+
+    #[test]
+    fn access_status_expired() {
+        let g = grant(AccessTerms::OneTime, 1_000, 0);
+        assert_eq!(g.access_status(&g.requester, &fields(&["email"]), 200), AccessCheckStatus::Expired);
+    }
+
+    #[test]
+    fn access_status_missing_fields() {
+        let g = grant(AccessTerms::OneTime, 1_000, 0);
+        assert_eq!(
+            g.access_status(&g.requester, &fields(&["email", "wallet_history"]), 150),
+            AccessCheckStatus::MissingFields
+        );
+    }
+
+    #[test]
+    fn access_status_metered_budget_exhausted() {
+        let g = grant(AccessTerms::Metered { reads: 3 }, 1_000, 999);
+        assert_eq!(
+            g.access_status(&g.requester, &fields(&["email"]), 150),
+            AccessCheckStatus::BudgetExhausted
+        );
+
+        let g = grant(AccessTerms::Metered { reads: 3 }, 1_000, 1_000);
+        assert_eq!(g.access_status(&g.requester, &fields(&["email"]), 150), AccessCheckStatus::Granted);
+    }
+
+    #[test]
+    fn access_status_subscription_lapsed_before_crank() {
+        let terms = AccessTerms::Subscription { period: 100, periods: 2 };
+        let g = grant(terms, 1_000, 0);
+        assert_eq!(g.access_status(&g.requester, &fields(&["email"]), 150), AccessCheckStatus::Granted);
+        assert_eq!(
+            g.access_status(&g.requester, &fields(&["email"]), 200),
+            AccessCheckStatus::BudgetExhausted
+        );
+
+        let g = grant(terms, 1_000, 1_000);
+        assert_eq!(g.access_status(&g.requester, &fields(&["email"]), 200), AccessCheckStatus::Expired);
+    }
+
+    fn field_pricing(prices: &[(&str, u64)]) -> FieldPricing {
+        FieldPricing {
+            synid: Pubkey::new_unique(),
+            min_payment: 0,
+            payment_mint: None,
+            prices: prices
+                .iter()
+                .map(|(field, price)| FieldPrice {
+                    field: field.to_string(),
+                    price: *price,
+                    available: true,
+                })
+                .collect(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn partial_payment_weights_by_field_price() {
+        let pricing = field_pricing(&[("email", 3_000), ("country", 1_000)]);
+        let requested = fields(&["email", "country"]);
+        let granted = fields(&["country"]);
+        let payment = partial_payment(
+            8_000,
+            (pricing.price_of(&granted).unwrap(), granted.len()),
+            (pricing.price_of(&requested).unwrap(), requested.len()),
+        );
+        assert_eq!(payment, 2_000);
+    }
+
+    #[test]
+    fn partial_payment_with_zero_weight_fields() {
+        let pricing = field_pricing(&[("email", 3_000), ("country", 0)]);
+        let requested = fields(&["email", "country"]);
+
+        let granted = fields(&["country"]);
+        let payment = partial_payment(
+            8_000,
+            (pricing.price_of(&granted).unwrap(), granted.len()),
+            (pricing.price_of(&requested).unwrap(), requested.len()),
+        );
+        assert_eq!(payment, 0);
+
+        let granted = fields(&["email"]);
+        let payment = partial_payment(
+            8_000,
+            (pricing.price_of(&granted).unwrap(), granted.len()),
+            (pricing.price_of(&requested).unwrap(), requested.len()),
+        );
+        assert_eq!(payment, 8_000);
+    }
+
+    #[test]
+    fn partial_payment_falls_back_to_field_count() {
+        assert_eq!(partial_payment(9_000, (0, 1), (0, 3)), 3_000);
+        assert_eq!(partial_payment(10_000, (0, 2), (0, 3)), 6_666);
+    }
+
+    #[test]
+    fn unused_payment_over_the_term() {
+        let g = grant(AccessTerms::OneTime, 1_000, 0);
+        assert_eq!(g.unused_payment(100), 1_000);
+        assert_eq!(g.unused_payment(150), 500);
+        assert_eq!(g.unused_payment(199), 10);
+        assert_eq!(g.unused_payment(200), 0);
+        assert_eq!(g.unused_payment(250), 0);
+    }
+
+    #[test]
+    fn unused_payment_is_zero_for_metered_grants() {
+        let g = grant(AccessTerms::Metered { reads: 4 }, 1_000, 4_000);
+        assert_eq!(g.unused_payment(100), 0);
+    }
+
+    fn error_code(result: Result<u64>) -> u32 {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => error.error_code_number,
+            Error::ProgramError(error) => panic!("unexpected program error: {error}"),
+        }
+    }
+
+    #[test]
+    fn subscription_deposit_covers_every_period() {
+        let terms = AccessTerms::Subscription { period: 86_400, periods: 12 };
+        assert_eq!(terms.deposit(1_000).unwrap(), 12_000);
+    }
+
+    #[test]
+    fn subscription_deposit_overflow() {
+        let terms = AccessTerms::Subscription { period: 86_400, periods: 2 };
+        assert_eq!(error_code(terms.deposit(u64::MAX)), u32::from(SynidError::MathOverflow));
+        assert_eq!(terms.deposit(u64::MAX / 2).unwrap(), u64::MAX - 1);
+    }
+
+    #[test]
+    fn subscription_deposit_rejects_empty_terms() {
+        let terms = AccessTerms::Subscription { period: 0, periods: 2 };
+        assert_eq!(error_code(terms.deposit(1_000)), u32::from(SynidError::UnsupportedTerms));
+        let terms = AccessTerms::Subscription { period: 86_400, periods: 0 };
+        assert_eq!(error_code(terms.deposit(1_000)), u32::from(SynidError::UnsupportedTerms));
+    }
+
+    #[test]
+    fn metered_deposit_prepays_every_read() {
+        let terms = AccessTerms::Metered { reads: 250 };
+        assert_eq!(terms.deposit(4_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn metered_deposit_overflow() {
+        let terms = AccessTerms::Metered { reads: u32::MAX };
+        assert_eq!(error_code(terms.deposit(u64::MAX / 1_000)), u32::from(SynidError::MathOverflow));
+        assert_eq!(
+            error_code(AccessTerms::Metered { reads: 0 }.deposit(1)),
+            u32::from(SynidError::UnsupportedTerms)
+        );
+    }
+
+    fn config(fee_tiers: Vec<FeeTier>) -> Config {
+        Config {
+            authority: Pubkey::new_unique(),
+            mint_count: 0,
+            mint_price: 0,
+            access_fee: 0,
+            treasury: Pubkey::new_unique(),
+            paused: false,
+            total_revenue: 0,
+            bump: 255,
+            require_registered_requester: false,
+            refund_policy: RefundPolicy::None,
+            accepted_mints: Vec::new(),
+            platform_fee_bps: Config::DEFAULT_PLATFORM_FEE_BPS,
+            fee_tiers,
+            referral_share_bps: 0,
+            synid_token_program: token::ID,
+            collection_mint: Pubkey::default(),
+        }
+    }
+
+    fn requester_profile(category: RequesterCategory, verified: bool) -> RequesterProfile {
+        RequesterProfile {
+            authority: Pubkey::new_unique(),
+            display_name: "app".to_string(),
+            domain: "app.example".to_string(),
+            logo_uri: String::new(),
+            encryption_pubkey: [0u8; 32],
+            category,
+            verified,
+            reputation_score: 100,
+            created_at: 0,
+            updated_at: 0,
+            bump: 255,
+        }
+    }
+
+    fn tiers() -> Vec<FeeTier> {
+        vec![
+            FeeTier {
+                condition: FeeTierCondition::RequesterCategory(RequesterCategory::Game),
+                fee_bps: 300,
+            },
+            FeeTier {
+                condition: FeeTierCondition::OwnerReputation { min_score: 500 },
+                fee_bps: 400,
+            },
+            FeeTier {
+                condition: FeeTierCondition::OwnerReputation { min_score: 800 },
+                fee_bps: 200,
+            },
+        ]
+    }
+
+    #[test]
+    fn fee_bps_defaults_without_matching_tier() {
+        let config = config(tiers());
+        let profile = requester_profile(RequesterCategory::Defi, true);
+        assert_eq!(config.fee_bps(Some(&profile), 100), Config::DEFAULT_PLATFORM_FEE_BPS);
+        assert_eq!(config.fee_bps(None, 499), Config::DEFAULT_PLATFORM_FEE_BPS);
+    }
+
+    #[test]
+    fn fee_bps_picks_the_lowest_matching_tier() {
+        let config = config(tiers());
+        let game = requester_profile(RequesterCategory::Game, true);
+        assert_eq!(config.fee_bps(None, 500), 400);
+        assert_eq!(config.fee_bps(Some(&game), 500), 300);
+        assert_eq!(config.fee_bps(Some(&game), 800), 200);
+    }
+
+    #[test]
+    fn fee_bps_ignores_category_of_unverified_requester() {
+        let config = config(tiers());
+        let game = requester_profile(RequesterCategory::Game, false);
+        assert_eq!(config.fee_bps(Some(&game), 100), Config::DEFAULT_PLATFORM_FEE_BPS);
+    }
+
+    #[test]
+    fn fee_tiers_never_raise_the_base_rate() {
+        let mut config = config(vec![FeeTier {
+            condition: FeeTierCondition::OwnerReputation { min_score: 0 },
+            fee_bps: 1_500,
+        }]);
+        assert_eq!(config.fee_bps(None, 100), Config::DEFAULT_PLATFORM_FEE_BPS);
+        config.platform_fee_bps = 2_000;
+        assert_eq!(config.fee_bps(None, 100), 1_500);
+    }
+
+    #[test]
+    fn platform_fee_in_basis_points() {
+        assert_eq!(platform_fee(1_000_000, 500), 50_000);
+        assert_eq!(platform_fee(1_999, 500), 99);
+        assert_eq!(platform_fee(u64::MAX, 10_000), u64::MAX);
+        assert_eq!(platform_fee(1_000_000, 0), 0);
+    }
+
+    #[test]
+    fn split_payment_without_referrer() {
+        assert_eq!(split_payment(1_000_000, 500, 0), (950_000, 50_000, 0));
+    }
+
+    #[test]
+    fn split_payment_shares_the_platform_fee_with_the_referrer() {
+        let (owner_payment, platform_fee, referral_fee) = split_payment(1_000_000, 500, 2_000);
+        assert_eq!((owner_payment, platform_fee, referral_fee), (950_000, 50_000, 10_000));
+        assert_eq!(owner_payment + platform_fee, 1_000_000);
+        assert_eq!(split_payment(1_000_000, 500, 10_000), (950_000, 50_000, 50_000));
+    }
+
+    #[test]
+    fn split_payment_rounds_fees_down() {
+        assert_eq!(split_payment(1_999, 500, 3_333), (1_900, 99, 32));
+        assert_eq!(split_payment(19, 500, 5_000), (19, 0, 0));
+    }
+
+    fn max_fields() -> Vec<String> {
+        (0..AccessRequest::MAX_FIELDS)
+            .map(|i| format!("{i:0>width$}", width = FieldPricing::MAX_FIELD_LEN))
+            .collect()
+    }
+
+    #[test]
+    fn max_field_lists_fit_request_and_grant() {
+        let request = AccessRequest {
+            synid: Pubkey::new_unique(),
+            requester: Pubkey::new_unique(),
+            request_id: u64::MAX,
+            fields: max_fields(),
+            offered_payment: u64::MAX,
+            created_at: 0,
+            expires_at: 0,
+            encryption_pubkey: [0u8; 32],
+            terms: AccessTerms::Subscription { period: 1, periods: 1 },
+            payment_mint: Some(Pubkey::new_unique()),
+            referrer: Some(Pubkey::new_unique()),
+            status: AccessStatus::Countered,
+            counter_offer: Some(CounterOffer {
+                payment: u64::MAX,
+                expires_at: 0,
+                fields: max_fields(),
+                proposed_at: 0,
+            }),
+            bump: 255,
+        };
+        assert!(request.try_to_vec().unwrap().len() <= AccessRequest::SIZE);
+
+        let mut g = grant(AccessTerms::Subscription { period: 1, periods: 1 }, u64::MAX, 0);
+        g.requested_fields = max_fields();
+        g.fields = max_fields();
+        g.payment_mint = Some(Pubkey::new_unique());
+        g.referrer = Some(Pubkey::new_unique());
+        assert!(g.try_to_vec().unwrap().len() <= AccessGrant::SIZE);
+    }
 }
@@ -0,0 +1,135 @@
+use serde_json::{json, Value};
+
+use crate::SynidAccount;
+
+pub const SYMBOL: &str = "SYNID";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationTier {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl ReputationTier {
+    pub fn from_score(score: u16) -> Self {
+        match score {
+            800.. => ReputationTier::Platinum,
+            500..=799 => ReputationTier::Gold,
+            250..=499 => ReputationTier::Silver,
+            _ => ReputationTier::Bronze,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReputationTier::Bronze => "Bronze",
+            ReputationTier::Silver => "Silver",
+            ReputationTier::Gold => "Gold",
+            ReputationTier::Platinum => "Platinum",
+        }
+    }
+}
+
+impl std::fmt::Display for ReputationTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub fn metadata_json(synid: &SynidAccount, name: &str, image: &str) -> Value {
+    json!({
+        "name": name,
+        "symbol": SYMBOL,
+        "image": image,
+        "attributes": [
+            { "trait_type": "verified", "value": synid.verified },
+            {
+                "trait_type": "reputation_tier",
+                "value": ReputationTier::from_score(synid.reputation_score).as_str(),
+            },
+            { "trait_type": "token_id", "value": synid.token_id },
+        ],
+        "properties": {
+            "category": "image",
+            "files": [{ "uri": image }],
+        },
+    })
+}
+
+pub fn render_metadata_json(synid: &SynidAccount, name: &str, image: &str) -> String {
+    metadata_json(synid, name, image).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+
+    fn synid(verified: bool, reputation_score: u16, token_id: u64) -> SynidAccount {
+        SynidAccount {
+            owner: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            encrypted_cid: String::new(),
+            encryption_key_hash: [0u8; 32],
+            created_at: 0,
+            updated_at: 0,
+            token_id,
+            soulbound: true,
+            access_count: 0,
+            total_earnings: 0,
+            reputation_score,
+            verified,
+            bump: 255,
+            token_program: Pubkey::new_unique(),
+            has_field_pricing: false,
+            has_requester_list: false,
+        }
+    }
+
+    fn attribute(json: &Value, trait_type: &str) -> Value {
+        json["attributes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["trait_type"] == trait_type)
+            .map(|a| a["value"].clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn renders_standard_fields_and_attributes() {
+        let rendered = render_metadata_json(&synid(true, 520, 42), "alice", "https://img/alice.png");
+        let json: Value = serde_json::from_str(&rendered).unwrap();
+
+        assert_eq!(json["name"], "alice");
+        assert_eq!(json["symbol"], SYMBOL);
+        assert_eq!(json["image"], "https://img/alice.png");
+        assert_eq!(json["properties"]["files"][0]["uri"], "https://img/alice.png");
+        assert_eq!(attribute(&json, "verified"), true);
+        assert_eq!(attribute(&json, "reputation_tier"), "Gold");
+        assert_eq!(attribute(&json, "token_id"), 42);
+    }
+
+    #[test]
+    fn renders_unverified_identity() {
+        let json = metadata_json(&synid(false, 100, 1), "bob", "ipfs://bob");
+
+        assert_eq!(attribute(&json, "verified"), false);
+        assert_eq!(attribute(&json, "reputation_tier"), "Bronze");
+        assert_eq!(attribute(&json, "token_id"), 1);
+    }
+
+    #[test]
+    fn tier_boundaries() {
+        assert_eq!(ReputationTier::from_score(0), ReputationTier::Bronze);
+        assert_eq!(ReputationTier::from_score(249), ReputationTier::Bronze);
+        assert_eq!(ReputationTier::from_score(250), ReputationTier::Silver);
+        assert_eq!(ReputationTier::from_score(499), ReputationTier::Silver);
+        assert_eq!(ReputationTier::from_score(500), ReputationTier::Gold);
+        assert_eq!(ReputationTier::from_score(799), ReputationTier::Gold);
+        assert_eq!(ReputationTier::from_score(800), ReputationTier::Platinum);
+        assert_eq!(ReputationTier::from_score(1000), ReputationTier::Platinum);
+    }
+}
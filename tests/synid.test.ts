@@ -1,10 +1,12 @@
-import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js"
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js"
 import SynidSDK from "../sdk"
 
 const TEST_CONFIG = {
   rpcUrl: "https://api.devnet.solana.com",
 }
 
+const PROGRAM_ID = new PublicKey("SYNiD1111111111111111111111111111111111111")
+
 async function runTests() {
   const sdk = new SynidSDK(TEST_CONFIG)
 
@@ -20,7 +22,15 @@ async function runTests() {
   const [mintAuthority] = await sdk.getMintAuthorityPDA()
   console.log("Mint Authority PDA:", mintAuthority.toString())
 
-  const [escrow] = await sdk.getEscrowPDA()
+  const requestId = Buffer.alloc(8)
+  requestId.writeBigUInt64LE(BigInt(0))
+  const [accessRequest] = PublicKey.findProgramAddressSync(
+    [Buffer.from("access_request"), synidPDA.toBuffer(), testWallet.publicKey.toBuffer(), requestId],
+    PROGRAM_ID
+  )
+  console.log("Access Request PDA:", accessRequest.toString())
+
+  const [escrow] = PublicKey.findProgramAddressSync([Buffer.from("escrow"), accessRequest.toBuffer()], PROGRAM_ID)
   console.log("Escrow PDA:", escrow.toString())
 
   const balance = await sdk.getBalance(testWallet.publicKey)